@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::calendar::{Day, Year};
+use crate::input::InputProvider;
+use crate::registry::{Entry, Registry};
+use crate::solution::Part;
+
+pub const ANSWERS_PATH: &str = "answers.toml";
+
+/// Accepted answers for our own inputs, read from a small subset of TOML:
+///
+/// ```toml
+/// [2020.day3]
+/// part1 = 294
+/// part2 = "5774564250"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct KnownAnswers {
+    answers: BTreeMap<(Year, Day, Part), String>,
+}
+
+impl KnownAnswers {
+    pub fn load(path: &str) -> Result<KnownAnswers, String> {
+        let data = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+        KnownAnswers::parse(&data).map_err(|error| format!("{}: {}", path, error))
+    }
+
+    pub fn parse(data: &str) -> Result<KnownAnswers, String> {
+        let mut answers: BTreeMap<(Year, Day, Part), String> = BTreeMap::new();
+        let mut section: Option<(Year, Day)> = None;
+
+        for (index, line) in data.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                let parsed = name.trim().split_once(".day").and_then(|(year, day)| {
+                    Some((year.parse::<Year>().ok()?, day.parse::<Day>().ok()?))
+                });
+                section = Some(parsed.ok_or_else(|| format!("line {}: expected [YEAR.dayN] but found {}", line_number, line))?);
+                continue;
+            }
+
+            let (year, day) = section.ok_or_else(|| format!("line {}: answer outside of a [YEAR.dayN] section", line_number))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected partN = answer but found {}", line_number, line))?;
+            let part = key
+                .trim()
+                .strip_prefix("part")
+                .and_then(|number| number.parse::<u8>().ok())
+                .and_then(Part::from_number)
+                .ok_or_else(|| format!("line {}: unknown key {}", line_number, key.trim()))?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|rest| rest.strip_suffix('"'))
+                .unwrap_or(value);
+            if answers.insert((year, day, part), value.to_string()).is_some() {
+                return Err(format!("line {}: {} day {} {} is repeated", line_number, year, day, part));
+            }
+        }
+
+        Ok(KnownAnswers { answers })
+    }
+
+    pub fn get(&self, year: Year, day: Day, part: Part) -> Option<&str> {
+        self.answers.get(&(year, day, part)).map(|answer| answer.as_str())
+    }
+
+    /// Years with at least one answer, in order
+    pub fn years(&self) -> Vec<Year> {
+        let mut years: Vec<Year> = self.answers.keys().map(|&(year, _, _)| year).collect();
+        years.dedup();
+        years
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String, actual: String },
+    /// there is no accepted answer to compare with
+    Unknown { actual: String },
+    Failed { error: String },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Verification {
+    pub year: Year,
+    pub day: Day,
+    pub part: Part,
+    pub verdict: Verdict,
+}
+
+impl Verification {
+    /// Wrong answers and failures are regressions, unknown answers are not
+    pub fn is_regression(&self) -> bool {
+        matches!(self.verdict, Verdict::Wrong { .. } | Verdict::Failed { .. })
+    }
+}
+
+/// Solves both parts of a day with its input and compares them with the known answers
+pub fn verify_day(entry: &Entry, answers: &KnownAnswers, inputs: &dyn InputProvider) -> Vec<Verification> {
+    let parsed = inputs
+        .input(entry.year, entry.day)
+        .map_err(|error| error.to_string())
+        .and_then(|input| entry.puzzle.parse(&input));
+
+    Part::ALL
+        .iter()
+        .map(|&part| {
+            let actual = parsed.as_ref().map_err(|error| error.clone()).and_then(|parsed| {
+                entry.puzzle.answer(parsed.as_ref(), part)
+            });
+            let verdict = match (actual, answers.get(entry.year, entry.day, part)) {
+                (Err(error), _) => Verdict::Failed { error },
+                (Ok(actual), None) => Verdict::Unknown { actual },
+                (Ok(actual), Some(expected)) if actual == expected => Verdict::Correct,
+                (Ok(actual), Some(expected)) => Verdict::Wrong { expected: expected.to_string(), actual },
+            };
+            Verification { year: entry.year, day: entry.day, part, verdict }
+        })
+        .collect()
+}
+
+/// Verifies a single day, or every registered day of `year` when `day` is `None`
+pub fn verify(registry: &Registry, answers: &KnownAnswers, year: Year, day: Option<Day>,
+              inputs: &dyn InputProvider) -> Result<Vec<Verification>, String> {
+    match day {
+        Some(day) => {
+            let entry = registry.get(year, day).ok_or_else(|| format!("{} day {} is not registered", year, day))?;
+            Ok(verify_day(entry, answers, inputs))
+        }
+        None => match registry.entries_of(year) {
+            entries if entries.is_empty() => Err(format!("no day of {} is registered", year)),
+            entries => Ok(entries.iter().flat_map(|entry| verify_day(entry, answers, inputs)).collect()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod answers_tests {
+    use crate::answers::{verify_day, KnownAnswers, Verdict, ANSWERS_PATH};
+    use crate::calendar::{Day, Year};
+    use crate::input::ResourcesDir;
+    use crate::registry::Registry;
+    use crate::solution::Part;
+
+    #[test]
+    fn answers_are_parsed() {
+        let data = "# comment\n[2020.day1]\npart1 = 123\npart2 = \"abc\"\n\n[2021.day12]\npart2=7\n";
+        let answers = KnownAnswers::parse(data).unwrap();
+        assert_eq!(Some("123"), answers.get(Year(2020), Day(1), Part::One));
+        assert_eq!(Some("abc"), answers.get(Year(2020), Day(1), Part::Two));
+        assert_eq!(None, answers.get(Year(2021), Day(12), Part::One));
+        assert_eq!(Some("7"), answers.get(Year(2021), Day(12), Part::Two));
+        assert_eq!(None, answers.get(Year(2020), Day(12), Part::Two));
+        assert_eq!(vec![Year(2020), Year(2021)], answers.years());
+    }
+
+    #[test]
+    fn invalid_answers_are_rejected() {
+        assert!(KnownAnswers::parse("part1 = 1").is_err());
+        assert!(KnownAnswers::parse("[2020.dayX]").is_err());
+        assert!(KnownAnswers::parse("[day1]").is_err());
+        assert!(KnownAnswers::parse("[2020.day26]").is_err());
+        assert!(KnownAnswers::parse("[2020.day1]\npart3 = 1").is_err());
+        assert!(KnownAnswers::parse("[2020.day1]\npart1 = 1\npart1 = 2").is_err());
+        assert!(KnownAnswers::parse("[2020.day1]\npart1").is_err());
+    }
+
+    #[test]
+    fn wrong_answer_is_a_regression() {
+        let registry = Registry::default();
+        let answers = KnownAnswers::parse("[2020.day3]\npart1 = 1\n").unwrap();
+        let verifications = verify_day(registry.get(Year(2020), Day(3)).unwrap(), &answers, &ResourcesDir::default());
+
+        assert_eq!(Verdict::Wrong { expected: "1".to_string(), actual: "294".to_string() }, verifications[0].verdict);
+        assert!(verifications[0].is_regression());
+        assert!(matches!(verifications[1].verdict, Verdict::Unknown { .. }));
+        assert!(!verifications[1].is_regression());
+    }
+
+    /// One test per registered day, each one fails when an answer differs from `answers.toml`.
+    /// `aoc new` adds days here marked `#[ignore]` until their answers are known
+    macro_rules! verify_days {
+        ($($(#[$attribute:meta])* $name:ident: $year:expr, $day:expr,)*) => {
+            $(
+                #[test]
+                $(#[$attribute])*
+                fn $name() {
+                    let registry = Registry::default();
+                    let answers = KnownAnswers::load(ANSWERS_PATH).unwrap();
+                    let inputs = ResourcesDir::default();
+                    for verification in verify_day(registry.get(Year($year), Day($day)).unwrap(), &answers, &inputs) {
+                        assert_eq!(Verdict::Correct, verification.verdict, "{}", verification.part);
+                    }
+                }
+            )*
+
+            #[test]
+            fn every_registered_day_is_verified() {
+                let registered: Vec<(Year, Day)> =
+                    Registry::default().entries().iter().map(|entry| (entry.year, entry.day)).collect();
+                assert_eq!(vec![$((Year($year), Day($day))),*], registered);
+            }
+        };
+    }
+
+    verify_days! {
+        verify_2020_day1: 2020, 1,
+        verify_2020_day2: 2020, 2,
+        verify_2020_day3: 2020, 3,
+        verify_2020_day4: 2020, 4,
+        verify_2020_day5: 2020, 5,
+        verify_2020_day6: 2020, 6,
+        verify_2020_day7: 2020, 7,
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::calendar::{Day, Year};
+use crate::registry::Entry;
+use crate::solution::Part;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Parse,
+    Solve(Part),
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Solve(part) => write!(f, "{}", part),
+        }
+    }
+}
+
+pub struct BenchConfig {
+    /// runs that are not timed, they only warm up caches and the allocator
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig { warmup: 3, iterations: 20 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Nearest rank percentiles, panics when there are no samples
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let percentile = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).max(1) - 1];
+        Stats { min: sorted[0], median: percentile(0.5), p95: percentile(0.95) }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchResult {
+    pub year: Year,
+    pub day: Day,
+    pub phase: Phase,
+    pub stats: Stats,
+}
+
+impl BenchResult {
+    /// Identifies the same measurement across baselines, such as `2020.day7 part 2`
+    pub fn key(&self) -> String {
+        format!("{}.day{} {}", self.year, self.day, self.phase)
+    }
+}
+
+fn time<T, F: FnMut() -> Result<T, String>>(config: &BenchConfig, mut run: F) -> Result<Stats, String> {
+    for _ in 0..config.warmup {
+        run()?;
+    }
+    let mut samples: Vec<Duration> = Vec::with_capacity(config.iterations);
+    for _ in 0..config.iterations.max(1) {
+        let start = Instant::now();
+        run()?;
+        samples.push(start.elapsed());
+    }
+    Ok(Stats::from_samples(&samples))
+}
+
+/// Times parsing and each part separately, parts always reuse the same parsed input
+pub fn bench_day(entry: &Entry, input: &str, config: &BenchConfig) -> Result<Vec<BenchResult>, String> {
+    let mut results: Vec<BenchResult> = Vec::new();
+
+    let stats = time(config, || entry.puzzle.parse(input))?;
+    results.push(BenchResult { year: entry.year, day: entry.day, phase: Phase::Parse, stats });
+
+    let parsed = entry.puzzle.parse(input)?;
+    for &part in Part::ALL.iter() {
+        let stats = time(config, || entry.puzzle.answer(parsed.as_ref(), part))?;
+        results.push(BenchResult { year: entry.year, day: entry.day, phase: Phase::Solve(part), stats });
+    }
+    Ok(results)
+}
+
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    match nanos {
+        0..=9_999 => format!("{} ns", nanos),
+        10_000..=9_999_999 => format!("{:.1} µs", nanos as f64 / 1e3),
+        _ => format!("{:.1} ms", nanos as f64 / 1e6),
+    }
+}
+
+pub fn render_table(results: &[BenchResult]) -> String {
+    let mut table = format!("{:<6} {:<6} {:<8} {:>12} {:>12} {:>12}\n", "year", "day", "phase", "min", "median", "p95");
+    for result in results {
+        table.push_str(&format!(
+            "{:<6} {:<6} {:<8} {:>12} {:>12} {:>12}\n",
+            result.year.to_string(),
+            result.day.to_string(),
+            result.phase.to_string(),
+            format_duration(result.stats.min),
+            format_duration(result.stats.median),
+            format_duration(result.stats.p95)
+        ));
+    }
+    table
+}
+
+/// Baseline file, one object per measurement with durations in nanoseconds
+pub fn to_json(results: &[BenchResult]) -> String {
+    let entries: Vec<String> = results
+        .iter()
+        .map(|result| {
+            format!(
+                "  \"{}\": {{\"min_ns\": {}, \"median_ns\": {}, \"p95_ns\": {}}}",
+                result.key(),
+                result.stats.min.as_nanos(),
+                result.stats.median.as_nanos(),
+                result.stats.p95.as_nanos()
+            )
+        })
+        .collect();
+    format!("{{\n{}\n}}\n", entries.join(",\n"))
+}
+
+/// Reads back what `to_json` writes, keyed by `BenchResult::key`
+pub fn parse_baseline(json: &str) -> Result<BTreeMap<String, Stats>, String> {
+    let mut cursor = JsonCursor { input: json, position: 0 };
+    let mut baseline: BTreeMap<String, Stats> = BTreeMap::new();
+
+    cursor.expect('{')?;
+    if cursor.peek() == Some('}') {
+        cursor.expect('}')?;
+        cursor.expect_end()?;
+        return Ok(baseline);
+    }
+    loop {
+        let key = cursor.string()?;
+        cursor.expect(':')?;
+        cursor.expect('{')?;
+        let mut fields: BTreeMap<String, u64> = BTreeMap::new();
+        loop {
+            let field = cursor.string()?;
+            cursor.expect(':')?;
+            fields.insert(field, cursor.number()?);
+            if !cursor.comma_or('}')? {
+                break;
+            }
+        }
+        let field = |name: &str| {
+            fields
+                .get(name)
+                .map(|&nanos| Duration::from_nanos(nanos))
+                .ok_or_else(|| format!("{} is missing {}", key, name))
+        };
+        let stats = Stats { min: field("min_ns")?, median: field("median_ns")?, p95: field("p95_ns")? };
+        baseline.insert(key, stats);
+        if !cursor.comma_or('}')? {
+            break;
+        }
+    }
+    cursor.expect_end()?;
+    Ok(baseline)
+}
+
+/// Just enough of a JSON reader for baseline files
+struct JsonCursor<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> JsonCursor<'a> {
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+        self.input[self.position..].chars().next()
+    }
+
+    fn error(&mut self, expected: &str) -> String {
+        let found = self.peek().map_or(String::from("end of input"), |c| format!("'{}'", c));
+        format!("expected {} but found {} at position {}", expected, found, self.position)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("'{}'", expected)));
+        }
+        self.position += expected.len_utf8();
+        Ok(())
+    }
+
+    /// `true` after a comma, `false` after the closing character
+    fn comma_or(&mut self, close: char) -> Result<bool, String> {
+        match self.peek() {
+            Some(',') => self.expect(',').map(|_| true),
+            Some(c) if c == close => self.expect(close).map(|_| false),
+            _ => Err(self.error(&format!("',' or '{}'", close))),
+        }
+    }
+
+    /// Only whitespace may follow the top level value
+    fn expect_end(&mut self) -> Result<(), String> {
+        match self.peek() {
+            Some(_) => Err(self.error("end of input")),
+            None => Ok(()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let rest = &self.input[self.position..];
+        let end = rest.find('"').ok_or_else(|| self.error("closing '\"'"))?;
+        self.position += end + 1;
+        Ok(rest[..end].to_string())
+    }
+
+    fn number(&mut self) -> Result<u64, String> {
+        self.peek();
+        let rest = &self.input[self.position..];
+        let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let number = rest[..end].parse::<u64>().map_err(|_| self.error("a number"))?;
+        self.position += end;
+        Ok(number)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Slowdown {
+    pub key: String,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Slowdown {
+    /// How much slower than the baseline, 0.5 means 50% slower
+    pub fn ratio(&self) -> f64 {
+        self.current.as_secs_f64() / self.baseline.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0
+    }
+}
+
+/// Measurements whose median got slower than the baseline by more than `threshold`,
+/// 0.1 meaning 10%. Measurements missing from the baseline are ignored
+pub fn compare(results: &[BenchResult], baseline: &BTreeMap<String, Stats>, threshold: f64) -> Vec<Slowdown> {
+    results
+        .iter()
+        .filter_map(|result| {
+            let before = baseline.get(&result.key())?;
+            let slowdown = Slowdown { key: result.key(), baseline: before.median, current: result.stats.median };
+            if slowdown.ratio() > threshold { Some(slowdown) } else { None }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod bench_tests {
+    use std::time::Duration;
+    use crate::bench::{bench_day, compare, parse_baseline, render_table, to_json, BenchConfig, BenchResult, Phase, Stats};
+    use crate::calendar::{Day, Year};
+    use crate::registry::Registry;
+    use crate::solution::Part;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn result(day: u8, phase: Phase, median: u64) -> BenchResult {
+        BenchResult { year: Year(2020), day: Day(day), phase, stats: Stats { min: ms(median - 1), median: ms(median), p95: ms(median + 1) } }
+    }
+
+    #[test]
+    fn stats_use_nearest_rank() {
+        let samples: Vec<Duration> = (1..=20).rev().map(ms).collect();
+        assert_eq!(Stats { min: ms(1), median: ms(10), p95: ms(19) }, Stats::from_samples(&samples));
+        assert_eq!(Stats { min: ms(4), median: ms(4), p95: ms(4) }, Stats::from_samples(&[ms(4)]));
+    }
+
+    #[test]
+    fn every_phase_is_timed() {
+        let registry = Registry::default();
+        let config = BenchConfig { warmup: 1, iterations: 3 };
+        let results = bench_day(registry.get(Year(2020), Day(6)).unwrap(), "abc\n\na\nb\n", &config).unwrap();
+
+        let phases: Vec<Phase> = results.iter().map(|result| result.phase).collect();
+        assert_eq!(vec![Phase::Parse, Phase::Solve(Part::One), Phase::Solve(Part::Two)], phases);
+        assert!(render_table(&results).contains("part 2"));
+    }
+
+    #[test]
+    fn baseline_survives_json() {
+        let results = vec![result(1, Phase::Parse, 5), result(7, Phase::Solve(Part::Two), 30)];
+        let baseline = parse_baseline(&to_json(&results)).unwrap();
+
+        assert_eq!(2, baseline.len());
+        assert_eq!(results[1].stats, baseline["2020.day7 part 2"]);
+        assert!(parse_baseline("{}").unwrap().is_empty());
+        assert!(parse_baseline(" {}\n").unwrap().is_empty());
+        assert_eq!(
+            Err(String::from("expected end of input but found '}' at position 2")),
+            parse_baseline("{}}")
+        );
+        assert!(parse_baseline(&format!("{}garbage", to_json(&results))).is_err());
+        assert!(parse_baseline("{\"2020.day1 parse\": {\"min_ns\": 1}}").is_err());
+    }
+
+    #[test]
+    fn slowdowns_above_threshold_are_flagged() {
+        let baseline = parse_baseline(&to_json(&[
+            result(1, Phase::Parse, 10),
+            result(1, Phase::Solve(Part::One), 10),
+        ])).unwrap();
+        let current = vec![
+            result(1, Phase::Parse, 11),
+            result(1, Phase::Solve(Part::One), 15),
+            result(2, Phase::Parse, 100),
+        ];
+
+        let slowdowns = compare(&current, &baseline, 0.2);
+        assert_eq!(1, slowdowns.len());
+        assert_eq!("2020.day1 part 1", slowdowns[0].key);
+        assert!((slowdowns[0].ratio() - 0.5).abs() < 1e-9);
+    }
+}
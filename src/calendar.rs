@@ -0,0 +1,68 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// An Advent of Code event, the first one was held in 2015
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Year(pub u16);
+
+impl Year {
+    pub const FIRST: Year = Year(2015);
+}
+
+impl fmt::Display for Year {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Year {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Year, String> {
+        match value.parse::<u16>() {
+            Ok(year) if year >= Year::FIRST.0 => Ok(Year(year)),
+            _ => Err(format!("invalid year {}", value)),
+        }
+    }
+}
+
+/// A day of an event, from 1 to 25
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Day(pub u8);
+
+impl Day {
+    pub const LAST: Day = Day(25);
+}
+
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Day {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Day, String> {
+        match value.parse::<u8>() {
+            Ok(day) if (1..=Day::LAST.0).contains(&day) => Ok(Day(day)),
+            _ => Err(format!("invalid day {}", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod calendar_tests {
+    use crate::calendar::{Day, Year};
+
+    #[test]
+    fn calendar_values_are_parsed() {
+        assert_eq!(Ok(Year(2020)), "2020".parse::<Year>());
+        assert!("2014".parse::<Year>().is_err());
+        assert!("twenty".parse::<Year>().is_err());
+
+        assert_eq!(Ok(Day(25)), "25".parse::<Day>());
+        assert!("0".parse::<Day>().is_err());
+        assert!("26".parse::<Day>().is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Tells a long running solver to give up. Cancellation is cooperative: nothing is
+/// interrupted, solvers have to poll `is_cancelled` and return early on their own.
+/// Clones share the same state, so any of them can cancel the others
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    /// Only cancelled by an explicit `cancel`
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Also cancelled once `timeout` has passed
+    pub fn with_timeout(timeout: Duration) -> Self {
+        CancellationToken { cancelled: Arc::new(AtomicBool::new(false)), deadline: Some(Instant::now() + timeout) }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst) || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+#[cfg(test)]
+mod cancel_tests {
+    use std::thread;
+    use std::time::Duration;
+    use crate::cancel::CancellationToken;
+
+    #[test]
+    fn clones_share_cancellation() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!token.is_cancelled());
+        clone.cancel();
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn timeout_cancels() {
+        let token = CancellationToken::with_timeout(Duration::from_millis(10));
+        assert!(!token.is_cancelled());
+        thread::sleep(Duration::from_millis(20));
+        assert!(token.is_cancelled());
+    }
+}
@@ -1,70 +1,144 @@
-mod day1 {
-    use rand;
-
-    /// find a and b where a+b=2020
-    pub fn find_double(items: &Vec<i32>) -> [i32; 2] {
-        let sum_amount = 2020;
-        let mut double: [i32; 2] = [0, 0];
-
-        for &current_value in items.iter() {
-            let expected = sum_amount - current_value;
-            if items.contains(&expected) {
-                double[0] = current_value;
-                double[1] = expected;
-                break;
-            }
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::SeedableRng;
+use crate::cancel::CancellationToken;
+use crate::solution::Solution;
+
+/// find a and b at two different positions where a+b=2020, `None` when there are none
+pub fn find_double(items: &[i32]) -> Option<[i32; 2]> {
+    let sum_amount = 2020;
+
+    for (index, &current_value) in items.iter().enumerate() {
+        let expected = sum_amount - current_value;
+        if items[index + 1..].contains(&expected) {
+            return Some([current_value, expected]);
         }
+    }
+
+    return None;
+}
+
+/// Seed of the search used by `find_triplet`, so answers and timings are reproducible
+pub const SEED: u64 = 2020;
+
+/// Draws allowed to `find_triplet`. A 200 item input has about 1.3 million triplets,
+/// so a single matching one is found well before this with near certainty
+pub const MAX_ATTEMPTS: u64 = 100_000_000;
+
+/// find a, b and c where a+b+c=2020, panics when there is no such triplet
+pub fn find_triplet(items: &[i32]) -> [i32; 3] {
+    return find_triplet_until(items, &CancellationToken::new()).expect("no triplet found");
+}
+
+/// find a, b and c where a+b+c=2020, or `None` when the search gives up or `token` is cancelled
+pub fn find_triplet_until(items: &[i32], token: &CancellationToken) -> Option<[i32; 3]> {
+    let search = RandomizedKSum::new(3, 2020, SEED, MAX_ATTEMPTS);
+    return search.search_until(items, token).items.map(|items| [items[0], items[1], items[2]]);
+}
+
+/// Looks for `k` items summing to `target` by drawing `k` distinct positions at random
+/// until they match. The seed makes runs reproducible and the attempt budget bounds them
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomizedKSum {
+    pub k: usize,
+    pub target: i32,
+    pub seed: u64,
+    pub max_attempts: u64,
+}
+
+/// What a `RandomizedKSum` search did
+#[derive(Debug, Clone, PartialEq)]
+pub struct KSumStats {
+    /// draws made, at most the attempt budget
+    pub attempts: u64,
+    /// the matching items in the order they were drawn, `None` when the search gave up
+    pub items: Option<Vec<i32>>,
+}
 
-        return double;
+impl KSumStats {
+    pub fn is_success(&self) -> bool {
+        self.items.is_some()
     }
+}
 
-    /// find a, b and c where a+b+c=2020
-    pub fn find_triplet(items: &Vec<i32>) -> [i32; 3] {
-        let total_nr_items = items.len();
-        let sum_amount = 2020;
-        let mut triplet: [usize; 3] = [0, 1, 2];
+impl RandomizedKSum {
+    pub fn new(k: usize, target: i32, seed: u64, max_attempts: u64) -> Self {
+        RandomizedKSum { k, target, seed, max_attempts }
+    }
+
+    pub fn search(&self, items: &[i32]) -> KSumStats {
+        self.search_until(items, &CancellationToken::new())
+    }
+
+    /// Also gives up once `token` is cancelled
+    pub fn search_until(&self, items: &[i32], token: &CancellationToken) -> KSumStats {
+        let mut stats = KSumStats { attempts: 0, items: None };
+        if self.k > items.len() {
+            return stats;
+        }
 
-        while items[triplet[0]] + items[triplet[1]] + items[triplet[2]] != sum_amount {
-            triplet = random_triplet(&total_nr_items);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        while stats.attempts < self.max_attempts && !token.is_cancelled() {
+            stats.attempts += 1;
+            let drawn: Vec<i32> = index::sample(&mut rng, items.len(), self.k).into_iter().map(|i| items[i]).collect();
+            if drawn.iter().map(|&item| i64::from(item)).sum::<i64>() == i64::from(self.target) {
+                stats.items = Some(drawn);
+                break;
+            }
         }
+        return stats;
+    }
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Parsed = Vec<i32>;
+
+    fn parse(&self, input: &str) -> Result<Vec<i32>, String> {
+        input
+            .lines()
+            .map(|line| line.parse::<i32>().map_err(|error| format!("invalid number \"{}\": {}", line, error)))
+            .collect()
+    }
+
+    fn part1(&self, items: &Vec<i32>) -> Result<String, String> {
+        let double = find_double(items).ok_or_else(|| String::from("no pair found"))?;
+        Ok((double[0] * double[1]).to_string())
+    }
 
-        return [items[triplet[0]], items[triplet[1]], items[triplet[2]]];
+    fn part2(&self, items: &Vec<i32>) -> Result<String, String> {
+        self.part2_cancellable(items, &CancellationToken::new())
     }
 
-    fn random_triplet(max_len: &usize) -> [usize; 3] {
-        return [
-            rand::random::<usize>() % max_len,
-            rand::random::<usize>() % max_len,
-            rand::random::<usize>() % max_len
-        ];
+    fn part2_cancellable(&self, items: &Vec<i32>, token: &CancellationToken) -> Result<String, String> {
+        let triplet = find_triplet_until(items, token).ok_or_else(|| String::from("no triplet found"))?;
+        Ok((triplet[0] * triplet[1] * triplet[2]).to_string())
     }
 }
 
+#[cfg(test)]
 mod day1_tests {
-    use std::fs::File;
-    use std::io::{BufReader, BufRead};
     use std::fs;
-    use crate::day1::{find_double, find_triplet};
+    use std::time::Duration;
+    use crate::calendar::{Day, Year};
+    use crate::cancel::CancellationToken;
+    use crate::day1::{find_double, find_triplet, find_triplet_until, Day1, RandomizedKSum, MAX_ATTEMPTS};
+    use crate::input::ResourcesDir;
+    use crate::manifest;
+    use crate::solution::Solution;
 
     #[test]
     fn input_is_valid() {
-        let file = File::open("resources/day-1-input").unwrap();
-        let reader = BufReader::new(file);
-
-        let mut lines = 0;
-        for line in reader.lines() {
-            assert!(line.unwrap().parse::<i32>().is_ok(), "Each line should be valid integer");
-            lines += 1;
-        }
-
-        assert_eq!(200, lines, "Input file is expected to have 200 lines");
+        let report = manifest::validate(&ResourcesDir::default(), Year(2020), Day(1)).unwrap();
+        assert!(report.is_valid(), "{}", report);
     }
 
     #[test]
     fn solve_part_1() {
         let items = get_items();
 
-        let double: [i32; 2] = find_double(&items);
+        let double: [i32; 2] = find_double(&items).unwrap();
         let sum = double[0] + double[1];
         let result = double[0] * double[1];
 
@@ -79,6 +153,15 @@ mod day1_tests {
         assert!(result > 0);
     }
 
+    #[test]
+    fn missing_pair_is_not_an_answer() {
+        assert_eq!(None, find_double(&[1010, 5]));
+        assert_eq!(None, find_double(&[]));
+        assert_eq!(Some([1010, 1010]), find_double(&[5, 1010, 1010]));
+        assert_eq!(Err(String::from("no pair found")), Day1.part1(&vec![1010, 5]));
+        assert_eq!(Err(String::from("no pair found")), Day1.part1(&vec![1, 2]));
+    }
+
     #[test]
     fn solve_part_2() {
         let items = get_items();
@@ -99,8 +182,71 @@ mod day1_tests {
         assert!(result > 0);
     }
 
+    #[test]
+    fn missing_triplet_gives_up_once_cancelled() {
+        let token = CancellationToken::with_timeout(Duration::from_millis(20));
+        assert_eq!(None, find_triplet_until(&[1, 2, 3, 4], &token));
+        assert_eq!(None, find_triplet_until(&[1010, 1010], &CancellationToken::new()));
+    }
+
+    #[test]
+    fn randomized_search_is_reproducible() {
+        let items = get_items();
+        let search = RandomizedKSum::new(3, 2020, 7, MAX_ATTEMPTS);
+        let first = search.search(&items);
+        assert!(first.is_success());
+        assert_eq!(2020, first.items.as_ref().unwrap().iter().sum::<i32>());
+        assert_eq!(first, search.search(&items));
+    }
+
+    #[test]
+    fn randomized_search_agrees_with_exhaustive_search() {
+        let items = get_items();
+        let mut exact: Vec<i32> = Vec::new();
+        for a in 0..items.len() {
+            for b in a + 1..items.len() {
+                for c in b + 1..items.len() {
+                    if items[a] + items[b] + items[c] == 2020 {
+                        exact = vec![items[a], items[b], items[c]];
+                    }
+                }
+            }
+        }
+
+        let mut found = RandomizedKSum::new(3, 2020, 2020, MAX_ATTEMPTS).search(&items).items.unwrap();
+        found.sort_unstable();
+        exact.sort_unstable();
+        assert_eq!(exact, found);
+    }
+
+    #[test]
+    fn randomized_search_respects_its_budget() {
+        let items = get_items();
+        let found = RandomizedKSum::new(3, 2020, 7, MAX_ATTEMPTS).search(&items);
+        let bounded = RandomizedKSum::new(3, 2020, 7, found.attempts - 1).search(&items);
+        assert_eq!(found.attempts - 1, bounded.attempts);
+        assert!(!bounded.is_success());
+    }
+
+    #[test]
+    fn randomized_search_draws_distinct_items() {
+        let search = RandomizedKSum::new(2, 2020, 1, 1000);
+        assert_eq!(Some(vec![1010, 1010]), search.search(&[1010, 1010, 5]).items);
+        let stats = search.search(&[1010, 5]);
+        assert_eq!(1000, stats.attempts);
+        assert!(!stats.is_success());
+        assert_eq!(0, RandomizedKSum::new(4, 2020, 1, 1000).search(&[1, 2, 3]).attempts);
+    }
+
+    #[test]
+    fn randomized_search_handles_large_items() {
+        let search = RandomizedKSum::new(2, 2020, 1, 1000);
+        assert!(!search.search(&[i32::MAX, i32::MAX, i32::MAX]).is_success());
+        assert_eq!(Some(2), search.search(&[i32::MAX, i32::MIN, 2020 - i32::MAX]).items.map(|items| items.len()));
+    }
+
     fn get_items() -> Vec<i32> {
-        return fs::read_to_string("resources/day-1-input")
+        return fs::read_to_string("resources/2020/day-1-input")
             .expect("file not found")
             .lines()
             .map(|item| item.parse::<i32>().unwrap())
@@ -1,47 +1,92 @@
-mod day2 {
-    pub struct PolicyWithPassword {
-        pub min_count: usize,
-        pub max_count: usize,
-        pub pass_char: char,
-        pub existing_password: Vec<char>,
-    }
+use crate::parser::{Cursor, ParseError};
+use crate::solution::Solution;
+
+pub struct PolicyWithPassword {
+    pub min_count: usize,
+    pub max_count: usize,
+    pub pass_char: char,
+    pub existing_password: Vec<char>,
+}
 
-    impl PolicyWithPassword {
-        pub fn from_slice(line: &str) -> PolicyWithPassword {
-            PolicyWithPassword::from_string(line.to_string())
+impl PolicyWithPassword {
+    /// Panics if the line is not a valid policy, see `parse`
+    pub fn from_slice(line: &str) -> PolicyWithPassword {
+        match PolicyWithPassword::parse(line) {
+            Ok(policy) => policy,
+            Err(error) => panic!("invalid policy \"{}\": {}", line, error),
         }
+    }
 
-        pub fn from_string(line: String) -> PolicyWithPassword {
-            let parts: Vec<&str> = line.split(
-                |c| c == ' ' || c == '-' || c == ':'
-            ).filter(
-                |part| !part.is_empty()
-            ).collect();
-
-            PolicyWithPassword {
-                min_count: parts.get(0).unwrap().parse::<usize>().unwrap(),
-                max_count: parts.get(1).unwrap().parse::<usize>().unwrap(),
-                pass_char: parts.get(2).unwrap().parse::<char>().unwrap(),
-                existing_password: parts.get(3).unwrap().chars().collect(),
-            }
-        }
+    pub fn from_string(line: String) -> PolicyWithPassword {
+        PolicyWithPassword::from_slice(&line)
+    }
 
-        pub fn is_valid(&self) -> bool {
-            let char_count = self.existing_password.iter().filter(|&c| c.eq(&self.pass_char)).count();
-            char_count >= self.min_count && char_count <= self.max_count
-        }
+    /// A line such as `1-3 a: abcde`, the counts are positions starting at 1 so 0 is refused
+    pub fn parse(line: &str) -> Result<PolicyWithPassword, ParseError> {
+        let mut cursor = Cursor::new(line);
+        let min_count = PolicyWithPassword::position(&mut cursor)?;
+        cursor.expect_char('-')?;
+        let max_count = PolicyWithPassword::position(&mut cursor)?;
+        let pass_char = cursor.char()?;
+        cursor.expect_char(':')?;
+        let existing_password = cursor.word()?.chars().collect();
+        cursor.expect_end()?;
+
+        Ok(PolicyWithPassword { min_count, max_count, pass_char, existing_password })
+    }
 
-        pub fn is_valid_with_position_check(&self) -> bool {
-            (self.existing_password[self.min_count - 1] == self.pass_char) ^
-                (self.existing_password[self.max_count - 1] == self.pass_char)
+    fn position(cursor: &mut Cursor) -> Result<usize, ParseError> {
+        let start = *cursor;
+        match cursor.number::<usize>() {
+            Ok(position) if position > 0 => Ok(position),
+            _ => Err(start.error("a position")),
         }
     }
+
+    pub fn is_valid(&self) -> bool {
+        let char_count = self.existing_password.iter().filter(|&c| c.eq(&self.pass_char)).count();
+        char_count >= self.min_count && char_count <= self.max_count
+    }
+
+    /// A position past the end of the password does not hold the letter
+    pub fn is_valid_with_position_check(&self) -> bool {
+        let holds_char = |position: usize| self.existing_password.get(position - 1) == Some(&self.pass_char);
+        holds_char(self.min_count) ^ holds_char(self.max_count)
+    }
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Parsed = Vec<PolicyWithPassword>;
+
+    fn parse(&self, input: &str) -> Result<Vec<PolicyWithPassword>, String> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| PolicyWithPassword::parse(line).map_err(|error| format!("line {}: {}", index + 1, error)))
+            .collect()
+    }
+
+    fn part1(&self, policies: &Vec<PolicyWithPassword>) -> Result<String, String> {
+        Ok(policies.iter().filter(|p| p.is_valid()).count().to_string())
+    }
+
+    fn part2(&self, policies: &Vec<PolicyWithPassword>) -> Result<String, String> {
+        Ok(policies.iter().filter(|p| p.is_valid_with_position_check()).count().to_string())
+    }
 }
 
+#[cfg(test)]
 mod day2_tests {
     use std::fs::File;
     use std::io::{BufReader, BufRead};
-    use crate::day2::{PolicyWithPassword};
+    use crate::calendar::{Day, Year};
+    use crate::day2::{Day2, PolicyWithPassword};
+    use crate::input::ResourcesDir;
+    use crate::manifest;
+    use crate::parser::ParseError;
+    use crate::solution::Solution;
 
     #[test]
     fn policy_is_extracted_from_string() {
@@ -67,6 +112,22 @@ mod day2_tests {
         assert_eq!(expected_pass, policy.existing_password);
     }
 
+    #[test]
+    fn malformed_policies_are_reported_with_their_position() {
+        assert_eq!(
+            Err(ParseError { position: 5, expected: "\":\"".to_string(), found: "\"t\"".to_string() }),
+            PolicyWithPassword::parse("1-2 tt: test").map(|policy| policy.pass_char)
+        );
+        assert_eq!(
+            Err(ParseError { position: 2, expected: "a position".to_string(), found: "\"0\"".to_string() }),
+            PolicyWithPassword::parse("1-0 t: test").map(|policy| policy.pass_char)
+        );
+        assert_eq!(
+            Err(String::from("line 2: expected a word but found end of line at position 7")),
+            Day2.parse("1-2 t: test\n1-2 t: ").map(|policies| policies.len())
+        );
+    }
+
     #[test]
     fn policy_validates_password() {
         let line: &str = "1-2 t: test";
@@ -103,21 +164,21 @@ mod day2_tests {
     }
 
     #[test]
-    fn input_is_valid() {
-        let file = File::open("resources/day-2-input").unwrap();
-        let reader = BufReader::new(file);
-
-        let mut policies: Vec<PolicyWithPassword> = Vec::new();
-        for line in reader.lines() {
-            policies.push(PolicyWithPassword::from_string(line.unwrap()))
-        }
+    fn policy_position_check_past_the_end_of_the_password() {
+        assert!(PolicyWithPassword::from_slice("1-9 a: abc").is_valid_with_position_check());
+        assert!(!PolicyWithPassword::from_slice("2-9 a: abc").is_valid_with_position_check());
+        assert!(!PolicyWithPassword::from_slice("8-9 a: abc").is_valid_with_position_check());
+    }
 
-        assert_eq!(1000, policies.len(), "Input file is expected to have 1000 lines");
+    #[test]
+    fn input_is_valid() {
+        let report = manifest::validate(&ResourcesDir::default(), Year(2020), Day(2)).unwrap();
+        assert!(report.is_valid(), "{}", report);
     }
 
     #[test]
     fn count_valid_passwords() {
-        let file = File::open("resources/day-2-input").unwrap();
+        let file = File::open("resources/2020/day-2-input").unwrap();
         let reader = BufReader::new(file);
 
         let mut policies: Vec<PolicyWithPassword> = Vec::new();
@@ -132,7 +193,7 @@ mod day2_tests {
 
     #[test]
     fn count_valid_passwords_with_position_check() {
-        let file = File::open("resources/day-2-input").unwrap();
+        let file = File::open("resources/2020/day-2-input").unwrap();
         let reader = BufReader::new(file);
 
         let mut policies: Vec<PolicyWithPassword> = Vec::new();
@@ -144,4 +205,4 @@ mod day2_tests {
         println!("Valid passwords with position check found={}", valid_passwords);
         assert!(valid_passwords > 0);
     }
-}
\ No newline at end of file
+}
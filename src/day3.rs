@@ -1,71 +1,109 @@
-mod day3 {
-    pub struct Line {
-        landscape: Vec<char>,
-    }
+use crate::solution::Solution;
 
-    impl Line {
-        pub fn from_string(input: &String) -> Line {
-            Line {
-                landscape: input.chars().collect()
-            }
-        }
-        pub fn from_slice(input: &str) -> Line {
-            Line::from_string(&input.to_string())
-        }
+pub struct Line {
+    landscape: Vec<char>,
+}
 
-        pub fn is_tree(&self, index: usize) -> bool {
-            self.landscape.get(index) == Some(&'#')
+impl Line {
+    pub fn from_string(input: &str) -> Line {
+        Line {
+            landscape: input.chars().collect()
         }
+    }
+    pub fn from_slice(input: &str) -> Line {
+        Line::from_string(input)
+    }
 
-        pub fn length(&self) -> usize {
-            self.landscape.len()
-        }
+    pub fn is_tree(&self, index: usize) -> bool {
+        self.landscape.get(index) == Some(&'#')
     }
 
-    pub struct Grid {
-        slope_right: usize,
-        slope_down: usize,
-        current_line: usize,
-        current_column: usize,
-        ingested_lines: usize,
-        pub tree_count: u64,
+    pub fn length(&self) -> usize {
+        self.landscape.len()
     }
+}
 
-    impl Grid {
-        pub fn new() -> Grid {
-            Grid::with_slopes(3, 1)
-        }
+pub struct Grid {
+    slope_right: usize,
+    slope_down: usize,
+    current_line: usize,
+    current_column: usize,
+    ingested_lines: usize,
+    pub tree_count: u64,
+}
 
-        pub fn with_slopes(slope_right: usize, slope_down: usize) -> Grid {
-            Grid {
-                slope_right,
-                slope_down,
-                current_line: 0,
-                current_column: 0,
-                ingested_lines: 0,
-                tree_count: 0,
-            }
+impl Default for Grid {
+    fn default() -> Self {
+        Grid::new()
+    }
+}
+
+impl Grid {
+    pub fn new() -> Grid {
+        Grid::with_slopes(3, 1)
+    }
+
+    pub fn with_slopes(slope_right: usize, slope_down: usize) -> Grid {
+        Grid {
+            slope_right,
+            slope_down,
+            current_line: 0,
+            current_column: 0,
+            ingested_lines: 0,
+            tree_count: 0,
         }
+    }
 
-        pub fn digest(&mut self, line: &Line) {
-            if self.should_process() {
-                if line.is_tree(self.current_column % line.length()) {
-                    self.tree_count += 1;
-                }
-                self.current_line += self.slope_down;
-                self.current_column += self.slope_right;
+    pub fn digest(&mut self, line: &Line) {
+        if self.should_process() {
+            if line.is_tree(self.current_column % line.length()) {
+                self.tree_count += 1;
             }
-            self.ingested_lines += 1;
+            self.current_line += self.slope_down;
+            self.current_column += self.slope_right;
         }
+        self.ingested_lines += 1;
+    }
 
-        fn should_process(&self) -> bool { self.ingested_lines == self.current_line }
+    fn should_process(&self) -> bool { self.ingested_lines == self.current_line }
+}
+
+pub struct Day3;
+
+impl Day3 {
+    fn count_trees(lines: &[Line], slope_right: usize, slope_down: usize) -> u64 {
+        let mut grid = Grid::with_slopes(slope_right, slope_down);
+        lines.iter().for_each(|line| grid.digest(line));
+        grid.tree_count
+    }
+}
+
+impl Solution for Day3 {
+    type Parsed = Vec<Line>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Line>, String> {
+        Ok(input.lines().map(Line::from_slice).collect())
+    }
+
+    fn part1(&self, lines: &Vec<Line>) -> Result<String, String> {
+        Ok(Day3::count_trees(lines, 3, 1).to_string())
+    }
+
+    fn part2(&self, lines: &Vec<Line>) -> Result<String, String> {
+        let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+        let product: u64 = slopes.iter().map(|&(right, down)| Day3::count_trees(lines, right, down)).product();
+        Ok(product.to_string())
     }
 }
 
+#[cfg(test)]
 mod day3_tests {
     use std::fs::File;
     use std::io::{BufReader, BufRead};
+    use crate::calendar::{Day, Year};
     use crate::day3::{Grid, Line};
+    use crate::input::ResourcesDir;
+    use crate::manifest;
 
     #[test]
     fn line_is_created() {
@@ -99,22 +137,13 @@ mod day3_tests {
 
     #[test]
     fn input_is_valid() {
-        let file = File::open("resources/day-3-input").unwrap();
-        let reader = BufReader::new(file);
-
-        let mut valid_lines: usize = 0;
-        for line in reader.lines() {
-            let invalid_chars =
-                line.unwrap().chars().filter(|&c| c != '#' && c != '.').count();
-            if invalid_chars == 0 { valid_lines += 1; }
-        }
-
-        assert_eq!(323, valid_lines, "Input file is expected to have 323 lines");
+        let report = manifest::validate(&ResourcesDir::default(), Year(2020), Day(3)).unwrap();
+        assert!(report.is_valid(), "{}", report);
     }
 
     #[test]
     fn count_trees() {
-        let file = File::open("resources/day-3-input").unwrap();
+        let file = File::open("resources/2020/day-3-input").unwrap();
         let reader = BufReader::new(file);
 
         let mut grid = Grid::new();
@@ -149,16 +178,16 @@ mod day3_tests {
 
     #[test]
     fn tree_product_of_multiple_slopes() {
-        let file = File::open("resources/day-3-input").unwrap();
+        let file = File::open("resources/2020/day-3-input").unwrap();
         let reader = BufReader::new(file);
 
-        let mut multi_grid: Vec<Grid> = Vec::new();
-
-        multi_grid.push(Grid::with_slopes(1, 1));
-        multi_grid.push(Grid::with_slopes(3, 1));
-        multi_grid.push(Grid::with_slopes(5, 1));
-        multi_grid.push(Grid::with_slopes(7, 1));
-        multi_grid.push(Grid::with_slopes(1, 2));
+        let mut multi_grid: Vec<Grid> = vec![
+            Grid::with_slopes(1, 1),
+            Grid::with_slopes(3, 1),
+            Grid::with_slopes(5, 1),
+            Grid::with_slopes(7, 1),
+            Grid::with_slopes(1, 2),
+        ];
 
         for line in reader.lines() {
             let parsed_line = Line::from_string(&line.unwrap());
@@ -176,4 +205,4 @@ mod day3_tests {
         println!("Product of number of trees encountered in all slopes {}", result);
         assert!(result > 1);
     }
-}
\ No newline at end of file
+}
@@ -1,123 +1,230 @@
-mod day4 {
-    use std::collections::HashMap;
+use std::collections::HashMap;
+use crate::parser::Cursor;
+use crate::solution::Solution;
 
-    pub struct Passport {
-        original_entries: HashMap<String, String>,
+const KNOWN_KEYS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+/// Something odd found while parsing a passport, `position` is the byte offset
+/// of the offending entry in the original input.
+#[derive(Debug, PartialEq)]
+pub struct ParseWarning {
+    pub position: usize,
+    pub kind: WarningKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WarningKind {
+    /// entry without any `:`, it is ignored
+    MissingColon { entry: String },
+    /// entry like `hcl:#a:b`, everything after the first `:` is kept as value
+    MultipleColons { key: String },
+    /// entry like `byr:`
+    EmptyValue { key: String },
+    /// key is not one of the known passport fields, it is ignored
+    UnknownKey { key: String },
+    /// same key and same value seen again
+    DuplicateKey { key: String, first_position: usize },
+    /// same key seen again with a different value, the first value is kept
+    ConflictingKey { key: String, first_position: usize, kept: String, ignored: String },
+}
+
+pub struct Passport {
+    original_entries: HashMap<String, String>,
+    warnings: Vec<ParseWarning>,
+}
+
+impl Passport {
+    pub fn from_slice(input: &str) -> Passport {
+        Passport::from(input.to_string())
     }
 
-    impl Passport {
-        pub fn from_slice(input: &str) -> Passport {
-            Passport::from(input.to_string())
-        }
+    /// Parsed fields to build the Passport
+    /// byr (Birth Year)
+    /// iyr (Issue Year)
+    /// eyr (Expiration Year)
+    /// hgt (Height)
+    /// hcl (Hair Color)
+    /// ecl (Eye Color)
+    /// pid (Passport ID)
+    /// cid (Country ID) // optional
+    pub fn from(input: String) -> Passport {
+        let mut entries: HashMap<String, String> = HashMap::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut warnings: Vec<ParseWarning> = Vec::new();
 
-        /// Parsed fields to build the Passport
-        /// byr (Birth Year)
-        /// iyr (Issue Year)
-        /// eyr (Expiration Year)
-        /// hgt (Height)
-        /// hcl (Hair Color)
-        /// ecl (Eye Color)
-        /// pid (Passport ID)
-        /// cid (Country ID) // optional
-        pub fn from(input: String) -> Passport {
-            let mut entries: HashMap<String, String> = HashMap::new();
-
-            for entry in input.split(|c| c == ' ' || c == '\n') {
-                if !entry.is_empty() {
-                    let key_value: Vec<&str> = entry.split(':').collect();
-                    let key = key_value.get(0).unwrap();
-                    let value = key_value.get(1).unwrap();
+        let mut cursor = Cursor::new(&input);
+        loop {
+            let position = cursor.position();
+            let entry = match cursor.word_until(&[]) {
+                Ok(entry) => entry,
+                Err(_) => break,
+            };
+            let mut fields = Cursor::new(entry);
+            let key = fields.word_until(&[':']).unwrap_or("");
+            if fields.expect_char(':').is_err() {
+                warnings.push(ParseWarning {
+                    position,
+                    kind: WarningKind::MissingColon { entry: entry.to_string() },
+                });
+                continue;
+            }
+            let value = fields.word_until(&[]).unwrap_or("");
+
+            if value.contains(':') {
+                warnings.push(ParseWarning {
+                    position,
+                    kind: WarningKind::MultipleColons { key: key.to_string() },
+                });
+            }
+            if value.is_empty() {
+                warnings.push(ParseWarning {
+                    position,
+                    kind: WarningKind::EmptyValue { key: key.to_string() },
+                });
+            }
+            if !KNOWN_KEYS.contains(&key) {
+                warnings.push(ParseWarning {
+                    position,
+                    kind: WarningKind::UnknownKey { key: key.to_string() },
+                });
+                continue;
+            }
+
+            match entries.get(key) {
+                Some(kept) => {
+                    let first_position = *positions.get(key).unwrap();
+                    let kind = if kept == value {
+                        WarningKind::DuplicateKey { key: key.to_string(), first_position }
+                    } else {
+                        WarningKind::ConflictingKey {
+                            key: key.to_string(),
+                            first_position,
+                            kept: kept.to_string(),
+                            ignored: value.to_string(),
+                        }
+                    };
+                    warnings.push(ParseWarning { position, kind });
+                }
+                None => {
                     entries.insert(key.to_string(), value.to_string());
+                    positions.insert(key.to_string(), position);
                 }
             }
-
-            Passport { original_entries: entries }
         }
 
-        pub fn has_valid_fields(&self) -> bool {
-            (self.original_entries.len() >= 7 && !self.original_entries.contains_key("cid")) ||
-                self.original_entries.len() == 8
-        }
+        Passport { original_entries: entries, warnings }
+    }
 
-        pub fn has_valid_data_in_fields(&self) -> bool {
-            return self.has_valid_fields()
-                && self.has_valid_birth_year()
-                && self.has_valid_issued_year()
-                && self.has_valid_expiration_year()
-                && self.has_valid_height()
-                && self.has_valid_hair_color()
-                && self.has_valid_eye_color()
-                && self.has_valid_passport_id();
-        }
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
 
-        /// byr - four digits; at least 1920 and at most 2002.
-        fn has_valid_birth_year(&self) -> bool {
-            let birth_year = self.original_entries.get("byr").unwrap().parse::<u16>().unwrap_or(0);
-            return birth_year >= 1920 && birth_year <= 2002;
-        }
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
 
-        /// iyr - four digits; at least 2010 and at most 2020.
-        fn has_valid_issued_year(&self) -> bool {
-            let issued_year = self.original_entries.get("iyr").unwrap().parse::<u16>().unwrap_or(0);
-            return issued_year >= 2010 && issued_year <= 2020;
-        }
+    pub fn has_valid_fields(&self) -> bool {
+        (self.original_entries.len() >= 7 && !self.original_entries.contains_key("cid")) ||
+            self.original_entries.len() == 8
+    }
 
-        /// eyr - four digits; at least 2020 and at most 2030.
-        fn has_valid_expiration_year(&self) -> bool {
-            let expiration_year = self.original_entries.get("eyr").unwrap().parse::<u16>().unwrap_or(0);
-            return expiration_year >= 2020 && expiration_year <= 2030;
-        }
+    pub fn has_valid_data_in_fields(&self) -> bool {
+        return self.has_valid_fields()
+            && self.has_valid_birth_year()
+            && self.has_valid_issued_year()
+            && self.has_valid_expiration_year()
+            && self.has_valid_height()
+            && self.has_valid_hair_color()
+            && self.has_valid_eye_color()
+            && self.has_valid_passport_id();
+    }
 
-        /// hgt - a number followed by either cm or in:
-        /// If cm, the number must be at least 150 and at most 193.
-        /// If in, the number must be at least 59 and at most 76.
-        fn has_valid_height(&self) -> bool {
-            let default_height = &String::from("0cm");
-            let height = self.original_entries.get("hgt").unwrap_or(default_height);
-            let (height_value, height_unit) = height.split_at(height.len() - 2);
-            return match height_unit {
-                "in" => {
-                    let height: u8 = height_value.parse::<u8>().unwrap_or(0);
-                    height >= 59 && height <= 76
-                }
-                "cm" => {
-                    let height: u8 = height_value.parse::<u8>().unwrap_or(0);
-                    height >= 150 && height <= 193
-                }
-                _ => false
-            };
-        }
+    /// byr - four digits; at least 1920 and at most 2002.
+    fn has_valid_birth_year(&self) -> bool {
+        let birth_year = self.original_entries.get("byr").unwrap().parse::<u16>().unwrap_or(0);
+        return (1920..=2002).contains(&birth_year);
+    }
 
-        /// hcl - a # followed by exactly six characters 0-9 or a-f.
-        fn has_valid_hair_color(&self) -> bool {
-            let default_hair_color = &String::from("");
-            let hair_color = self.original_entries.get("hcl").unwrap_or(default_hair_color);
-            let nr_hex_color_chars = hair_color.chars().filter(char::is_ascii_alphanumeric).count();
-            let control_char = hair_color.chars().nth(0).unwrap_or(' ');
-            return control_char == '#' && nr_hex_color_chars == 6;
-        }
+    /// iyr - four digits; at least 2010 and at most 2020.
+    fn has_valid_issued_year(&self) -> bool {
+        let issued_year = self.original_entries.get("iyr").unwrap().parse::<u16>().unwrap_or(0);
+        return (2010..=2020).contains(&issued_year);
+    }
 
-        const VALID_EYE_COLORS: [&'static str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+    /// eyr - four digits; at least 2020 and at most 2030.
+    fn has_valid_expiration_year(&self) -> bool {
+        let expiration_year = self.original_entries.get("eyr").unwrap().parse::<u16>().unwrap_or(0);
+        return (2020..=2030).contains(&expiration_year);
+    }
 
-        /// ecl - exactly one of: amb blu brn gry grn hzl oth
-        fn has_valid_eye_color(&self) -> bool {
-            let default_eye_color = &String::from("");
-            let eye_color = self.original_entries.get("ecl").unwrap_or(default_eye_color);
-            return Passport::VALID_EYE_COLORS.contains(&eye_color.as_str());
+    /// hgt - a number followed by either cm or in:
+    /// If cm, the number must be at least 150 and at most 193.
+    /// If in, the number must be at least 59 and at most 76.
+    fn has_valid_height(&self) -> bool {
+        let default_height = &String::from("0cm");
+        let height = self.original_entries.get("hgt").unwrap_or(default_height);
+        if let Some(height_value) = height.strip_suffix("in") {
+            let height: u8 = height_value.parse::<u8>().unwrap_or(0);
+            return (59..=76).contains(&height);
         }
-
-        /// pid - a nine-digit number, including leading zeroes.
-        fn has_valid_passport_id(&self) -> bool {
-            let default_passport_id = &String::from("0");
-            let passport_id = self.original_entries.get("pid").unwrap_or(default_passport_id);
-            return passport_id.chars().filter(char::is_ascii_digit).count() == 9;
+        if let Some(height_value) = height.strip_suffix("cm") {
+            let height: u8 = height_value.parse::<u8>().unwrap_or(0);
+            return (150..=193).contains(&height);
         }
+        return false;
+    }
+
+    /// hcl - a # followed by exactly six characters 0-9 or a-f.
+    fn has_valid_hair_color(&self) -> bool {
+        let default_hair_color = &String::from("");
+        let hair_color = self.original_entries.get("hcl").unwrap_or(default_hair_color);
+        let nr_hex_color_chars = hair_color.chars().filter(char::is_ascii_alphanumeric).count();
+        let control_char = hair_color.chars().nth(0).unwrap_or(' ');
+        return control_char == '#' && nr_hex_color_chars == 6;
+    }
+
+    const VALID_EYE_COLORS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+    /// ecl - exactly one of: amb blu brn gry grn hzl oth
+    fn has_valid_eye_color(&self) -> bool {
+        let default_eye_color = &String::from("");
+        let eye_color = self.original_entries.get("ecl").unwrap_or(default_eye_color);
+        return Passport::VALID_EYE_COLORS.contains(&eye_color.as_str());
+    }
+
+    /// pid - a nine-digit number, including leading zeroes.
+    fn has_valid_passport_id(&self) -> bool {
+        let default_passport_id = &String::from("0");
+        let passport_id = self.original_entries.get("pid").unwrap_or(default_passport_id);
+        return passport_id.chars().filter(char::is_ascii_digit).count() == 9;
     }
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Parsed = Vec<Passport>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Passport>, String> {
+        Ok(input.split("\n\n").map(Passport::from_slice).collect())
+    }
+
+    fn part1(&self, passports: &Vec<Passport>) -> Result<String, String> {
+        Ok(passports.iter().filter(|passport| passport.has_valid_fields()).count().to_string())
+    }
+
+    fn part2(&self, passports: &Vec<Passport>) -> Result<String, String> {
+        Ok(passports.iter().filter(|passport| passport.has_valid_data_in_fields()).count().to_string())
+    }
+}
+
+#[cfg(test)]
 mod day4_tests {
     use std::fs;
-    use crate::day4::Passport;
+    use crate::calendar::{Day, Year};
+    use crate::day4::{Passport, ParseWarning, WarningKind};
+    use crate::input::ResourcesDir;
+    use crate::manifest;
 
     #[test]
     fn valid_passport_format() {
@@ -149,17 +256,16 @@ mod day4_tests {
 
     #[test]
     fn input_is_valid() {
-        let data: String = fs::read_to_string("resources/day-4-input").unwrap();
-        let unparsed_passport: Vec<&str> = data.split("\n\n").collect();
-        assert_eq!(291, unparsed_passport.len())
+        let report = manifest::validate(&ResourcesDir::default(), Year(2020), Day(4)).unwrap();
+        assert!(report.is_valid(), "{}", report);
     }
 
     #[test]
     fn valid_passports_found() {
-        let data: String = fs::read_to_string("resources/day-4-input").unwrap();
+        let data: String = fs::read_to_string("resources/2020/day-4-input").unwrap();
         let valid_passports: usize =
             data.split("\n\n")
-                .map(|item| Passport::from_slice(item))
+                .map(Passport::from_slice)
                 .filter(|passport| passport.has_valid_fields())
                 .count();
         println!("Found {} valid passports", valid_passports);
@@ -173,6 +279,15 @@ mod day4_tests {
         assert!(!passport.has_valid_data_in_fields())
     }
 
+    #[test]
+    fn input_is_valid_but_height_is_too_short_to_have_a_unit() {
+        for height in &["", "7", "é", "1é"] {
+            let input = format!("byr:1990 iyr:2015 eyr:2025 hgt:{} hcl:#123456 ecl:amb pid:123456789", height);
+            let passport = Passport::from_slice(&input);
+            assert!(!passport.has_valid_data_in_fields(), "{}", height);
+        }
+    }
+
     #[test]
     fn input_is_valid_but_expiration_year_is_in_the_past() {
         let input = "iyr:2019 hcl:#602927 eyr:1967 hgt:170cm ecl:grn pid:012533040 byr:1946";
@@ -224,13 +339,68 @@ mod day4_tests {
 
     #[test]
     fn valid_passports_found_with_valid_data() {
-        let data: String = fs::read_to_string("resources/day-4-input").unwrap();
+        let data: String = fs::read_to_string("resources/2020/day-4-input").unwrap();
         let valid_passports: usize =
             data.split("\n\n")
-                .map(|item| Passport::from_slice(item))
+                .map(Passport::from_slice)
                 .filter(|passport| passport.has_valid_data_in_fields())
                 .count();
         println!("Found {} valid passports", valid_passports);
         assert!(valid_passports > 0)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn well_formed_passport_has_no_warnings() {
+        let input = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\nbyr:1937 iyr:2017 cid:147 hgt:183cm";
+        let passport = Passport::from_slice(input);
+        assert!(!passport.has_warnings())
+    }
+
+    #[test]
+    fn duplicate_key_with_same_value_is_reported() {
+        let input = "byr:1990 iyr:2017 byr:1990";
+        let passport = Passport::from_slice(input);
+        assert_eq!(
+            passport.warnings(),
+            &[ParseWarning {
+                position: 18,
+                kind: WarningKind::DuplicateKey { key: "byr".to_string(), first_position: 0 },
+            }]
+        );
+    }
+
+    #[test]
+    fn conflicting_key_keeps_first_value() {
+        let input = "iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884\nhcl:#cfa07d byr:1929 hgt:170cm byr:2001";
+        let passport = Passport::from_slice(input);
+        assert_eq!(
+            passport.warnings(),
+            &[ParseWarning {
+                position: 79,
+                kind: WarningKind::ConflictingKey {
+                    key: "byr".to_string(),
+                    first_position: 60,
+                    kept: "1929".to_string(),
+                    ignored: "2001".to_string(),
+                },
+            }]
+        );
+        assert!(passport.has_valid_data_in_fields())
+    }
+
+    #[test]
+    fn malformed_entries_are_reported_without_panicking() {
+        let input = "byr iyr: hcl:#a:b xyz:1";
+        let passport = Passport::from_slice(input);
+        assert_eq!(
+            passport.warnings(),
+            &[
+                ParseWarning { position: 0, kind: WarningKind::MissingColon { entry: "byr".to_string() } },
+                ParseWarning { position: 4, kind: WarningKind::EmptyValue { key: "iyr".to_string() } },
+                ParseWarning { position: 9, kind: WarningKind::MultipleColons { key: "hcl".to_string() } },
+                ParseWarning { position: 18, kind: WarningKind::UnknownKey { key: "xyz".to_string() } },
+            ]
+        );
+        assert!(!passport.has_valid_fields())
+    }
+}
@@ -1,65 +1,406 @@
-mod day5 {
-    pub fn seat_id(input: &str) -> u16 {
-        row(input) * 8 + column(input)
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use crate::solution::Solution;
+
+/// Describes how a boarding pass is encoded: the first `row_bits` letters pick the row
+/// and the remaining `col_bits` letters pick the column. Each pair of letters is
+/// (lower half, upper half), so a boarding pass is just a binary number in disguise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardingPassLayout {
+    row_bits: usize,
+    col_bits: usize,
+    row_letters: (char, char),
+    col_letters: (char, char),
+}
+
+/// Why a `BoardingPassLayout` cannot be built
+#[derive(Debug, PartialEq)]
+pub enum LayoutError {
+    /// seat ids are `u32`, so rows and columns together need fewer than 32 letters
+    TooManyBits { bits: usize },
+    /// the four letters must all be different to tell the halves apart
+    RepeatedLetter { letter: char },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::TooManyBits { bits } =>
+                write!(f, "{} letters do not fit in a seat id, at most {} are allowed", bits, BoardingPassLayout::MAX_BITS),
+            LayoutError::RepeatedLetter { letter } =>
+                write!(f, "letter '{}' is used more than once", letter),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    WrongLength { expected: usize, found: usize },
+    /// letter that is not used anywhere in the layout
+    InvalidLetter { position: usize, letter: char },
+    /// column letter used to pick the row, or the other way around
+    LetterInWrongHalf { position: usize, letter: char },
+}
+
+impl DecodeError {
+    /// Character range of the input that caused the error, empty when characters are missing
+    pub fn span(&self) -> Range<usize> {
+        match *self {
+            DecodeError::WrongLength { expected, found } => expected.min(found)..found,
+            DecodeError::InvalidLetter { position, .. } => position..position + 1,
+            DecodeError::LetterInWrongHalf { position, .. } => position..position + 1,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::WrongLength { expected, found } =>
+                write!(f, "expected {} letters but found {}", expected, found),
+            DecodeError::InvalidLetter { letter, .. } =>
+                write!(f, "unknown letter '{}'", letter),
+            DecodeError::LetterInWrongHalf { position, letter } =>
+                write!(f, "letter '{}' is not allowed at position {}", letter, position),
+        }
+    }
+}
+
+/// A boarding pass that was fully validated against its layout
+#[derive(Debug, PartialEq)]
+pub struct BoardingPass {
+    pub row: u32,
+    pub column: u32,
+    pub seat_id: u32,
+}
+
+impl BoardingPass {
+    pub fn parse(input: &str) -> Result<BoardingPass, DecodeError> {
+        BoardingPass::parse_with_layout(&BoardingPassLayout::standard(), input)
+    }
+
+    pub fn parse_with_layout(layout: &BoardingPassLayout, input: &str) -> Result<BoardingPass, DecodeError> {
+        let seat_id = layout.seat_id(input)?;
+        Ok(BoardingPass {
+            row: seat_id >> layout.col_bits,
+            column: seat_id & (layout.columns() - 1),
+            seat_id,
+        })
+    }
+}
+
+/// A line that could not be parsed as a boarding pass, `line_number` starts at 1
+#[derive(Debug, PartialEq)]
+pub struct InvalidPass {
+    pub line_number: usize,
+    pub line: String,
+    pub error: DecodeError,
+}
+
+impl fmt::Display for InvalidPass {
+    /// Shows the line with the offending characters underlined
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = self.error.span();
+        let prefix = format!("line {}: ", self.line_number);
+        writeln!(f, "{}{}", prefix, self.line)?;
+        write!(
+            f,
+            "{}{} {}",
+            " ".repeat(prefix.len() + span.start),
+            "^".repeat((span.end - span.start).max(1)),
+            self.error
+        )
+    }
+}
+
+/// Every line of a `resources/2020/day-5-input`-like text that is not a valid boarding pass
+pub fn validate_passes(layout: &BoardingPassLayout, data: &str) -> Vec<InvalidPass> {
+    data.lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            BoardingPass::parse_with_layout(layout, line).err().map(|error| InvalidPass {
+                line_number: index + 1,
+                line: line.to_string(),
+                error,
+            })
+        })
+        .collect()
+}
+
+pub fn validate_file(layout: &BoardingPassLayout, path: &str) -> io::Result<Vec<InvalidPass>> {
+    Ok(validate_passes(layout, &fs::read_to_string(path)?))
+}
+
+impl BoardingPassLayout {
+    pub const MAX_BITS: usize = 31;
+
+    pub fn new(
+        row_bits: usize,
+        col_bits: usize,
+        row_letters: (char, char),
+        col_letters: (char, char),
+    ) -> Result<Self, LayoutError> {
+        let bits = row_bits + col_bits;
+        if bits > BoardingPassLayout::MAX_BITS {
+            return Err(LayoutError::TooManyBits { bits });
+        }
+        let letters = [row_letters.0, row_letters.1, col_letters.0, col_letters.1];
+        if let Some(index) = (1..letters.len()).find(|&index| letters[..index].contains(&letters[index])) {
+            return Err(LayoutError::RepeatedLetter { letter: letters[index] });
+        }
+        Ok(BoardingPassLayout { row_bits, col_bits, row_letters, col_letters })
+    }
+
+    /// 128 rows (F/B) and 8 columns (L/R)
+    pub fn standard() -> Self {
+        BoardingPassLayout {
+            row_bits: 7,
+            col_bits: 3,
+            row_letters: ('F', 'B'),
+            col_letters: ('L', 'R'),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.row_bits + self.col_bits
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn rows(&self) -> u32 {
+        1 << self.row_bits
+    }
+
+    pub fn columns(&self) -> u32 {
+        1 << self.col_bits
+    }
+
+    pub fn seat_id(&self, input: &str) -> Result<u32, DecodeError> {
+        self.decode_bits(input, 0, self.len())
+    }
+
+    pub fn row(&self, input: &str) -> Result<u32, DecodeError> {
+        self.decode_bits(input, 0, self.row_bits)
     }
 
-    pub fn row(input: &str) -> u16 {
-        let transformed_row = input.chars().take(7).map(|c| {
-            match c {
-                'B' => { 'H' }
-                'F' => { 'L' }
-                _ => '?'
+    pub fn column(&self, input: &str) -> Result<u32, DecodeError> {
+        self.decode_bits(input, self.row_bits, self.col_bits)
+    }
+
+    fn decode_bits(&self, input: &str, skip: usize, take: usize) -> Result<u32, DecodeError> {
+        let found = input.chars().count();
+        if found != self.len() {
+            return Err(DecodeError::WrongLength { expected: self.len(), found });
+        }
+
+        input.chars().enumerate().skip(skip).take(take).try_fold(0, |acc, (position, letter)| {
+            let (low, high) = if position < self.row_bits { self.row_letters } else { self.col_letters };
+            let (other_low, other_high) = if position < self.row_bits { self.col_letters } else { self.row_letters };
+            match letter {
+                c if c == low => Ok(acc << 1),
+                c if c == high => Ok(acc << 1 | 1),
+                c if c == other_low || c == other_high => Err(DecodeError::LetterInWrongHalf { position, letter }),
+                _ => Err(DecodeError::InvalidLetter { position, letter }),
             }
-        }).into_iter().collect();
-        binary_search(&transformed_row, 0, 127)
+        })
     }
 
-    pub fn column(input: &str) -> u16 {
-        let transformed_row = input.chars().skip(7).map(|c| {
-            match c {
-                'R' => { 'H' }
-                'L' => { 'L' }
-                _ => '?'
+    /// Panics if the row or column do not fit in this layout
+    pub fn encode(&self, row: u32, column: u32) -> String {
+        assert!(row < self.rows(), "row {} does not fit in {} rows", row, self.rows());
+        assert!(column < self.columns(), "column {} does not fit in {} columns", column, self.columns());
+        self.encode_id(row << self.col_bits | column)
+    }
+
+    /// Panics if the seat id does not fit in this layout
+    pub fn encode_id(&self, seat_id: u32) -> String {
+        assert!(seat_id < self.rows() * self.columns(), "seat id {} does not fit in this plane", seat_id);
+        (0..self.len())
+            .map(|position| {
+                let (low, high) = if position < self.row_bits { self.row_letters } else { self.col_letters };
+                let bit = self.len() - 1 - position;
+                if seat_id >> bit & 1 == 1 { high } else { low }
+            })
+            .collect()
+    }
+}
+
+/// A run of free seats in a single row
+#[derive(Debug, PartialEq)]
+pub struct FreeBlock {
+    pub row: u32,
+    pub column: u32,
+    pub len: u32,
+}
+
+/// Occupancy of every seat in the plane, one bit per seat id
+pub struct SeatMap {
+    layout: BoardingPassLayout,
+    occupied: Vec<u64>,
+    duplicates: Vec<u32>,
+}
+
+impl SeatMap {
+    pub fn from_passes<I, S>(layout: BoardingPassLayout, passes: I) -> Result<SeatMap, DecodeError>
+        where I: IntoIterator<Item=S>, S: AsRef<str> {
+        let nr_seats = (layout.rows() * layout.columns()) as usize;
+        let mut seat_map = SeatMap {
+            layout,
+            occupied: vec![0; nr_seats.div_ceil(64)],
+            duplicates: Vec::new(),
+        };
+
+        for pass in passes {
+            let id = seat_map.layout.seat_id(pass.as_ref())?;
+            if seat_map.is_occupied(id) {
+                seat_map.duplicates.push(id);
             }
-        }).into_iter().collect();
-        binary_search(&transformed_row, 0, 7)
-    }
-
-    fn binary_search(input: &String, lower_bound: u16, upper_bound: u16) -> u16 {
-        let mut lower: u16 = lower_bound;
-        let mut upper: u16 = upper_bound;
-        let all_but_last = input.len() - 1;
-        input.chars().take(all_but_last).for_each(|c| {
-            match c {
-                'H' => {
-                    let remainder = (upper - lower) % 2;
-                    lower += (upper - lower) / 2;
-                    lower += remainder;
-                }
-                'L' => {
-                    let remainder = (upper - lower) % 2;
-                    upper -= (upper - lower) / 2;
-                    upper -= remainder;
+            seat_map.occupied[id as usize / 64] |= 1 << (id % 64);
+        }
+
+        Ok(seat_map)
+    }
+
+    pub fn nr_seats(&self) -> u32 {
+        self.layout.rows() * self.layout.columns()
+    }
+
+    pub fn is_occupied(&self, seat_id: u32) -> bool {
+        seat_id < self.nr_seats() && self.occupied[seat_id as usize / 64] >> (seat_id % 64) & 1 == 1
+    }
+
+    /// Every occupied seat id, lowest first
+    pub fn occupied_seats(&self) -> Vec<u32> {
+        (0..self.nr_seats()).filter(|&id| self.is_occupied(id)).collect()
+    }
+
+    pub fn occupied_count(&self) -> u32 {
+        self.occupied.iter().map(|bits| bits.count_ones()).sum()
+    }
+
+    /// Empty seats whose ids on both sides are occupied
+    pub fn missing_seats(&self) -> Vec<u32> {
+        (1..self.nr_seats().saturating_sub(1))
+            .filter(|&id| !self.is_occupied(id) && self.is_occupied(id - 1) && self.is_occupied(id + 1))
+            .collect()
+    }
+
+    pub fn empty_rows(&self) -> Vec<u32> {
+        (0..self.layout.rows())
+            .filter(|&row| self.row_ids(row).all(|id| !self.is_occupied(id)))
+            .collect()
+    }
+
+    /// Every run of at least `len` free seats that does not cross a row
+    pub fn free_blocks(&self, len: u32) -> Vec<FreeBlock> {
+        let mut blocks: Vec<FreeBlock> = Vec::new();
+        for row in 0..self.layout.rows() {
+            let mut run_start: Option<u32> = None;
+            for column in 0..=self.layout.columns() {
+                let free = column < self.layout.columns() && !self.is_occupied(self.id(row, column));
+                match (free, run_start) {
+                    (true, None) => run_start = Some(column),
+                    (false, Some(start)) => {
+                        if column - start >= len {
+                            blocks.push(FreeBlock { row, column: start, len: column - start });
+                        }
+                        run_start = None;
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
-        });
-        return match input.chars().last().unwrap() {
-            'H' => { upper }
-            'L' => { lower }
-            _ => 0
-        };
+        }
+        blocks
+    }
+
+    /// Seat ids found in more than one boarding pass, once per extra pass
+    pub fn duplicates(&self) -> &[u32] {
+        &self.duplicates
     }
+
+    /// One line per row, `#` for occupied and `.` for free seats
+    pub fn render(&self) -> String {
+        (0..self.layout.rows())
+            .map(|row| {
+                self.row_ids(row)
+                    .map(|id| if self.is_occupied(id) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn id(&self, row: u32, column: u32) -> u32 {
+        row << self.layout.col_bits | column
+    }
+
+    fn row_ids(&self, row: u32) -> Range<u32> {
+        self.id(row, 0)..self.id(row + 1, 0)
+    }
+}
+
+/// Panics if the input is not a standard boarding pass
+pub fn seat_id(input: &str) -> u16 {
+    BoardingPassLayout::standard().seat_id(input).unwrap() as u16
 }
 
+/// Panics if the input is not a standard boarding pass
+pub fn row(input: &str) -> u16 {
+    BoardingPassLayout::standard().row(input).unwrap() as u16
+}
+
+/// Panics if the input is not a standard boarding pass
+pub fn column(input: &str) -> u16 {
+    BoardingPassLayout::standard().column(input).unwrap() as u16
+}
+
+pub fn encode(row: u16, column: u16) -> String {
+    BoardingPassLayout::standard().encode(row as u32, column as u32)
+}
+
+pub fn encode_id(seat_id: u16) -> String {
+    BoardingPassLayout::standard().encode_id(seat_id as u32)
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Parsed = SeatMap;
+
+    fn parse(&self, input: &str) -> Result<SeatMap, String> {
+        SeatMap::from_passes(BoardingPassLayout::standard(), input.lines()).map_err(|error| error.to_string())
+    }
+
+    fn part1(&self, seat_map: &SeatMap) -> Result<String, String> {
+        seat_map
+            .occupied_seats()
+            .last()
+            .map(|id| id.to_string())
+            .ok_or_else(|| String::from("no boarding passes"))
+    }
+
+    fn part2(&self, seat_map: &SeatMap) -> Result<String, String> {
+        match seat_map.missing_seats().as_slice() {
+            [id] => Ok(id.to_string()),
+            ids => Err(format!("expected a single missing seat but found {:?}", ids)),
+        }
+    }
+}
+
+#[cfg(test)]
 mod day5_tests {
     use std::fs::File;
     use std::io::{BufReader, BufRead};
-    use crate::day5::{seat_id, row, column};
+    use crate::day5::{seat_id, row, column, encode, encode_id, BoardingPassLayout, DecodeError, SeatMap, FreeBlock,
+                      BoardingPass, LayoutError, validate_passes, validate_file};
 
     #[test]
     fn solve_part1() {
-        let file = File::open("resources/day-5-input").unwrap();
+        let file = File::open("resources/2020/day-5-input").unwrap();
         let reader = BufReader::new(file);
 
         let biggest_seat_id = reader.lines()
@@ -116,28 +457,192 @@ mod day5_tests {
 
     #[test]
     fn solve_part2() {
-        let file = File::open("resources/day-5-input").unwrap();
+        let file = File::open("resources/2020/day-5-input").unwrap();
         let reader = BufReader::new(file);
 
-        let mut existing_ids: Vec<u16> = reader.lines()
-            .map(|line| seat_id(&line.unwrap()))
-            .collect();
-        existing_ids.sort();
-
-        let lowest_existing_id: u16 = *existing_ids.first().unwrap();
-        let highest_existing_id: u16 = *existing_ids.last().unwrap();
-
-        let remaining_ids: Vec<u16> =
-            (lowest_existing_id..highest_existing_id)
-                .step_by(1)
-                .filter(|&id| {
-                    !existing_ids.contains(&id)
-                        && existing_ids.contains(&(id - 1))
-                        && existing_ids.contains(&(id + 1))
-                })
-                .collect();
+        let passes: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
+        let seat_map = SeatMap::from_passes(BoardingPassLayout::standard(), &passes).unwrap();
+
+        let remaining_ids = seat_map.missing_seats();
 
         assert_eq!(remaining_ids.len(), 1);
         println!("Remaining seat ids: {:?}", remaining_ids);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn seat_id_is_the_binary_number_of_the_whole_pass() {
+        let layout = BoardingPassLayout::standard();
+        assert_eq!(Ok(0b1000110111), layout.seat_id("BFFFBBFRRR"));
+        assert_eq!(Ok(70 * 8 + 7), layout.seat_id("BFFFBBFRRR"));
+    }
+
+    #[test]
+    fn smaller_plane_is_decoded() {
+        let layout = BoardingPassLayout::new(2, 1, ('U', 'D'), ('W', 'A')).unwrap();
+        assert_eq!(Ok(2), layout.row("DUA"));
+        assert_eq!(Ok(1), layout.column("DUA"));
+        assert_eq!(Ok(5), layout.seat_id("DUA"));
+        assert_eq!(4, layout.rows());
+        assert_eq!(2, layout.columns());
+    }
+
+    #[test]
+    fn layouts_that_cannot_be_decoded_are_refused() {
+        assert_eq!(Err(LayoutError::TooManyBits { bits: 32 }), BoardingPassLayout::new(16, 16, ('F', 'B'), ('L', 'R')));
+        assert!(BoardingPassLayout::new(20, 11, ('F', 'B'), ('L', 'R')).is_ok());
+        assert_eq!(Err(LayoutError::RepeatedLetter { letter: 'F' }), BoardingPassLayout::new(7, 3, ('F', 'F'), ('L', 'R')));
+        assert_eq!(Err(LayoutError::RepeatedLetter { letter: 'B' }), BoardingPassLayout::new(7, 3, ('F', 'B'), ('B', 'R')));
+    }
+
+    #[test]
+    fn invalid_letter_is_an_error() {
+        let layout = BoardingPassLayout::standard();
+        assert_eq!(Err(DecodeError::InvalidLetter { position: 3, letter: 'X' }), layout.seat_id("BFFXBBFRRR"));
+        assert_eq!(Err(DecodeError::LetterInWrongHalf { position: 8, letter: 'F' }), layout.column("BFFFBBFRFR"));
+    }
+
+    #[test]
+    fn wrong_length_is_an_error() {
+        let layout = BoardingPassLayout::standard();
+        assert_eq!(Err(DecodeError::WrongLength { expected: 10, found: 7 }), layout.row("BFFFBBF"));
+        assert_eq!(Err(DecodeError::WrongLength { expected: 10, found: 0 }), layout.seat_id(""));
+    }
+
+    #[test]
+    fn specific_seats_are_encoded() {
+        assert_eq!("BFFFBBFRRR", encode(70, 7));
+        assert_eq!("FFFBBBFRRR", encode_id(119));
+        assert_eq!("BBFFBBFRLL", encode_id(820));
+    }
+
+    #[test]
+    fn every_seat_id_survives_encode_and_decode() {
+        let layout = BoardingPassLayout::standard();
+        for id in 0..1024 {
+            let pass = layout.encode_id(id);
+            assert_eq!(Ok(id), layout.seat_id(&pass), "seat id {} encoded as {}", id, pass);
+        }
+    }
+
+    #[test]
+    fn every_row_and_column_survives_encode_and_decode() {
+        let layout = BoardingPassLayout::standard();
+        for row in 0..layout.rows() {
+            for column in 0..layout.columns() {
+                let pass = layout.encode(row, column);
+                assert_eq!(Ok(row), layout.row(&pass));
+                assert_eq!(Ok(column), layout.column(&pass));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn seat_outside_of_the_plane_is_not_encoded() {
+        encode(128, 0);
+    }
+
+    fn small_layout() -> BoardingPassLayout {
+        BoardingPassLayout::new(2, 2, ('F', 'B'), ('L', 'R')).unwrap()
+    }
+
+    #[test]
+    fn seat_map_finds_missing_seats_and_empty_rows() {
+        let passes = ["FFLL", "FFLR", "FFRR", "FBLL", "FBLR"];
+        let seat_map = SeatMap::from_passes(small_layout(), passes).unwrap();
+
+        assert_eq!(5, seat_map.occupied_count());
+        assert_eq!(vec![2], seat_map.missing_seats());
+        assert_eq!(vec![2, 3], seat_map.empty_rows());
+        assert!(seat_map.duplicates().is_empty());
+    }
+
+    #[test]
+    fn seat_map_finds_free_blocks_within_rows() {
+        let passes = ["FFLR", "FBRL", "BFLL", "BBLL", "BBLR", "BBRL", "BBRR"];
+        let seat_map = SeatMap::from_passes(small_layout(), passes).unwrap();
+
+        assert_eq!(
+            vec![
+                FreeBlock { row: 0, column: 2, len: 2 },
+                FreeBlock { row: 1, column: 0, len: 2 },
+                FreeBlock { row: 2, column: 1, len: 3 },
+            ],
+            seat_map.free_blocks(2)
+        );
+        assert_eq!(vec![FreeBlock { row: 2, column: 1, len: 3 }], seat_map.free_blocks(3));
+    }
+
+    #[test]
+    fn seat_map_reports_duplicate_passes() {
+        let passes = ["FFLR", "BBRR", "FFLR", "FFLR"];
+        let seat_map = SeatMap::from_passes(small_layout(), passes).unwrap();
+
+        assert_eq!(&[1, 1], seat_map.duplicates());
+        assert_eq!(2, seat_map.occupied_count());
+    }
+
+    #[test]
+    fn seat_map_is_rendered_as_grid() {
+        let passes = ["FFLR", "FBRL", "BBRR"];
+        let seat_map = SeatMap::from_passes(small_layout(), passes).unwrap();
+
+        assert_eq!(".#..\n..#.\n....\n...#", seat_map.render());
+    }
+
+    #[test]
+    fn seat_map_rejects_invalid_pass() {
+        let passes = ["FFLR", "FXLR"];
+        let result = SeatMap::from_passes(small_layout(), passes);
+        assert_eq!(Some(DecodeError::InvalidLetter { position: 1, letter: 'X' }), result.err());
+    }
+
+    #[test]
+    fn boarding_pass_is_parsed() {
+        assert_eq!(Ok(BoardingPass { row: 102, column: 4, seat_id: 820 }), BoardingPass::parse("BBFFBBFRLL"));
+    }
+
+    #[test]
+    fn boarding_pass_with_letter_in_wrong_half_is_rejected() {
+        let error = BoardingPass::parse("BBFFBLFRLL").unwrap_err();
+        assert_eq!(DecodeError::LetterInWrongHalf { position: 5, letter: 'L' }, error);
+        assert_eq!(5..6, error.span());
+
+        let error = BoardingPass::parse("BBFFBBFRBL").unwrap_err();
+        assert_eq!(DecodeError::LetterInWrongHalf { position: 8, letter: 'B' }, error);
+    }
+
+    #[test]
+    fn boarding_pass_with_wrong_length_is_rejected() {
+        let error = BoardingPass::parse("BBFFBBFRLLRR").unwrap_err();
+        assert_eq!(DecodeError::WrongLength { expected: 10, found: 12 }, error);
+        assert_eq!(10..12, error.span());
+
+        let error = BoardingPass::parse("BBF").unwrap_err();
+        assert_eq!(3..3, error.span());
+    }
+
+    #[test]
+    fn every_bad_line_is_reported() {
+        let data = "BFFFBBFRRR\nBFFFBBFRR\nFFFBBBFRRR\nBFFFbBFRRR\nBBFFBBFRLL";
+        let invalid_passes = validate_passes(&BoardingPassLayout::standard(), data);
+
+        assert_eq!(2, invalid_passes.len());
+        assert_eq!(2, invalid_passes[0].line_number);
+        assert_eq!(DecodeError::WrongLength { expected: 10, found: 9 }, invalid_passes[0].error);
+        assert_eq!(4, invalid_passes[1].line_number);
+        assert_eq!(
+            "line 4: BFFFbBFRRR\n            ^ unknown letter 'b'",
+            invalid_passes[1].to_string()
+        );
+    }
+
+    #[test]
+    fn input_is_valid() {
+        let invalid_passes = validate_file(&BoardingPassLayout::standard(), "resources/2020/day-5-input").unwrap();
+        for invalid_pass in &invalid_passes {
+            println!("{}", invalid_pass);
+        }
+        assert!(invalid_passes.is_empty());
+    }
+}
@@ -1,55 +1,368 @@
-mod day6 {
-    use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::solution::Solution;
 
-    pub fn group_count(input: &str) -> usize {
-        let mut entry_count: HashMap<char, bool> = HashMap::new();
-        input
-            .chars()
-            .filter(char::is_ascii_alphabetic)
-            .for_each(|c| { entry_count.entry(c).or_insert(true); });
-
-        return entry_count.values().fold(0, |acc, &exists| {
-            if exists {
-                acc + 1
-            } else {
-                acc
+/// Which questions exist and how a character maps to one of them
+#[derive(Debug, Clone, PartialEq)]
+pub enum Alphabet {
+    /// `a` to `z`
+    Lowercase,
+    /// `a` to `z`, with `A` to `Z` being the same questions
+    CaseInsensitive,
+    /// any set of up to 128 characters, see `Alphabet::custom`
+    Custom(Vec<char>),
+}
+
+impl Alphabet {
+    /// The distinct characters of `questions`, which fit in `Answers` only up to 128
+    pub fn custom(questions: &str) -> Result<Alphabet, String> {
+        let mut chars: Vec<char> = Vec::new();
+        for c in questions.chars() {
+            if !chars.contains(&c) {
+                chars.push(c);
             }
-        });
+        }
+        if chars.len() > 128 {
+            return Err(format!("an alphabet can have at most 128 questions, found {}", chars.len()));
+        }
+        Ok(Alphabet::Custom(chars))
+    }
+
+    pub fn index_of(&self, c: char) -> Option<usize> {
+        match self {
+            Alphabet::Lowercase => match c {
+                'a'..='z' => Some(c as usize - 'a' as usize),
+                _ => None,
+            },
+            Alphabet::CaseInsensitive => Alphabet::Lowercase.index_of(c.to_ascii_lowercase()),
+            Alphabet::Custom(chars) => chars.iter().position(|&question| question == c),
+        }
+    }
+
+    pub fn question(&self, index: usize) -> Option<char> {
+        match self {
+            Alphabet::Lowercase | Alphabet::CaseInsensitive if index < 26 => Some((b'a' + index as u8) as char),
+            Alphabet::Custom(chars) => chars.get(index).copied(),
+            _ => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Alphabet::Lowercase | Alphabet::CaseInsensitive => 26,
+            Alphabet::Custom(chars) => chars.len(),
+        }
     }
 
-    pub fn group_count_all_in_common(input: &str) -> usize {
-        let nr_people_in_group =
-            input
-                .split("\n")
-                .filter(|&line| !line.is_empty())
-                .count();
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, answers: &Answers, c: char) -> bool {
+        self.index_of(c).is_some_and(|index| answers.contains_index(index))
+    }
+
+    /// Questions in `answers`, in alphabet order
+    pub fn questions(&self, answers: &Answers) -> Vec<char> {
+        (0..self.len())
+            .filter(|&index| answers.contains_index(index))
+            .filter_map(|index| self.question(index))
+            .collect()
+    }
+
+    /// Answers of a single person, every character must be a question
+    pub fn parse_person(&self, line: &str, line_number: usize) -> Result<Answers, Vec<InvalidAnswer>> {
+        let mut answers = Answers::default();
+        let mut errors: Vec<InvalidAnswer> = Vec::new();
+        for (position, character) in line.chars().enumerate() {
+            match self.index_of(character) {
+                Some(index) => answers = answers.union(&Answers::from_index(index)),
+                None => errors.push(InvalidAnswer { line_number, position, character }),
+            }
+        }
+        if errors.is_empty() { Ok(answers) } else { Err(errors) }
+    }
+}
 
-        let mut entry_count: HashMap<char, usize> = HashMap::new();
+/// Character that is not part of the alphabet, `line_number` starts at 1
+/// and `position` is the character offset in that line
+#[derive(Debug, PartialEq)]
+pub struct InvalidAnswer {
+    pub line_number: usize,
+    pub position: usize,
+    pub character: char,
+}
+
+impl fmt::Display for InvalidAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: '{}' at position {} is not a question", self.line_number, self.character, self.position)
+    }
+}
+
+/// Every character in a `resources/2020/day-6-input`-like text that is neither
+/// a question nor a line break
+pub fn validate_input(data: &str, alphabet: &Alphabet) -> Vec<InvalidAnswer> {
+    data.split('\n')
+        .enumerate()
+        .flat_map(|(index, line)| alphabet.parse_person(line, index + 1).err().unwrap_or_default())
+        .collect()
+}
+
+/// Strict parsing of every group in a `resources/2020/day-6-input`-like text, errors have
+/// line numbers in the whole input
+pub fn parse_groups(data: &str, alphabet: &Alphabet) -> Result<Vec<Group>, Vec<InvalidAnswer>> {
+    let mut groups: Vec<Group> = Vec::new();
+    let mut errors: Vec<InvalidAnswer> = Vec::new();
+    let mut lines_before = 0;
+    for chunk in data.split("\n\n") {
+        if !chunk.is_empty() {
+            match Group::parse(chunk, alphabet) {
+                Ok(group) => groups.push(group),
+                Err(chunk_errors) => errors.extend(
+                    chunk_errors
+                        .into_iter()
+                        .map(|error| InvalidAnswer { line_number: lines_before + error.line_number, ..error }),
+                ),
+            }
+        }
+        lines_before += chunk.split('\n').count() + 1;
+    }
+    if errors.is_empty() { Ok(groups) } else { Err(errors) }
+}
+
+/// Questions answered "yes" by someone, one bit per question of an `Alphabet`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Answers(u128);
+
+impl Answers {
+    /// Lenient parsing, anything other than `a` to `z` is ignored
+    pub fn from_slice(input: &str) -> Answers {
         input
             .chars()
-            .filter(char::is_ascii_alphabetic)
-            .for_each(|c| {
-                let entry = entry_count.entry(c).or_insert(0);
-                *entry += 1;
-            });
-
-        return entry_count.values().fold(0, |acc, &count| {
-            if count == nr_people_in_group {
-                acc + 1
-            } else {
-                acc
+            .filter_map(|c| Alphabet::Lowercase.index_of(c))
+            .fold(Answers::default(), |acc, index| acc.union(&Answers::from_index(index)))
+    }
+
+    fn from_index(index: usize) -> Answers {
+        Answers(1 << index)
+    }
+
+    pub fn contains_index(&self, index: usize) -> bool {
+        index < 128 && self.0 >> index & 1 == 1
+    }
+
+    /// Same as `Alphabet::Lowercase.contains`
+    pub fn contains(&self, c: char) -> bool {
+        Alphabet::Lowercase.contains(self, c)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(&self, other: &Answers) -> Answers {
+        Answers(self.0 | other.0)
+    }
+
+    pub fn intersection(&self, other: &Answers) -> Answers {
+        Answers(self.0 & other.0)
+    }
+
+    pub fn symmetric_difference(&self, other: &Answers) -> Answers {
+        Answers(self.0 ^ other.0)
+    }
+
+    /// Same as `Alphabet::Lowercase.questions`
+    pub fn questions(&self) -> Vec<char> {
+        Alphabet::Lowercase.questions(self)
+    }
+}
+
+/// Answers of each person in a group, one person per non empty line
+pub struct Group {
+    people: Vec<Answers>,
+}
+
+impl Group {
+    pub fn from_slice(input: &str) -> Group {
+        Group {
+            people: input.lines().filter(|line| !line.is_empty()).map(Answers::from_slice).collect(),
+        }
+    }
+
+    /// Strict parsing, every character that is not in `alphabet` is reported
+    pub fn parse(input: &str, alphabet: &Alphabet) -> Result<Group, Vec<InvalidAnswer>> {
+        let mut people: Vec<Answers> = Vec::new();
+        let mut errors: Vec<InvalidAnswer> = Vec::new();
+        for (index, line) in input.lines().enumerate().filter(|(_, line)| !line.is_empty()) {
+            match alphabet.parse_person(line, index + 1) {
+                Ok(answers) => people.push(answers),
+                Err(mut line_errors) => errors.append(&mut line_errors),
             }
-        });
+        }
+        if errors.is_empty() { Ok(Group { people }) } else { Err(errors) }
     }
 
-    pub fn group_chunk(input: &str) -> Vec<&str> {
-        input.split("\n\n").filter(|&line| !line.is_empty()).collect()
+    pub fn people(&self) -> &[Answers] {
+        &self.people
     }
+
+    /// Questions answered by anyone
+    pub fn union(&self) -> Answers {
+        self.people.iter().fold(Answers::default(), |acc, person| acc.union(person))
+    }
+
+    /// Questions answered by everyone, empty for a group without people
+    pub fn intersection(&self) -> Answers {
+        match self.people.split_first() {
+            Some((first, rest)) => rest.iter().fold(*first, |acc, person| acc.intersection(person)),
+            None => Answers::default(),
+        }
+    }
+
+    /// Questions answered by an odd number of people
+    pub fn symmetric_difference(&self) -> Answers {
+        self.people.iter().fold(Answers::default(), |acc, person| acc.symmetric_difference(person))
+    }
+
+    /// Questions answered by `k` or more people
+    pub fn at_least(&self, k: usize) -> Answers {
+        self.filter_by_count(|count| count >= k)
+    }
+
+    /// Questions answered by precisely `k` people
+    pub fn exactly(&self, k: usize) -> Answers {
+        self.filter_by_count(|count| count == k)
+    }
+
+    fn filter_by_count<F: Fn(usize) -> bool>(&self, predicate: F) -> Answers {
+        (0..128)
+            .filter(|&index| predicate(self.people.iter().filter(|person| person.contains_index(index)).count()))
+            .fold(Answers::default(), |acc, index| acc.union(&Answers::from_index(index)))
+    }
+}
+
+pub fn group_count(input: &str) -> usize {
+    Group::from_slice(input).union().len()
+}
+
+pub fn group_count_all_in_common(input: &str) -> usize {
+    Group::from_slice(input).intersection().len()
+}
+
+pub fn group_chunk(input: &str) -> Vec<&str> {
+    input.split("\n\n").filter(|&line| !line.is_empty()).collect()
 }
 
+/// How many groups and how many people answered a single question
+#[derive(Debug, PartialEq)]
+pub struct QuestionStats {
+    pub question: char,
+    pub groups: usize,
+    pub people: usize,
+}
+
+/// A group by its position in the input and number of people
+#[derive(Debug, PartialEq)]
+pub struct GroupSize {
+    pub index: usize,
+    pub people: usize,
+}
+
+pub struct Statistics {
+    pub questions: Vec<QuestionStats>,
+    /// first of the largest groups
+    pub largest_group: Option<GroupSize>,
+    /// first of the smallest groups
+    pub smallest_group: Option<GroupSize>,
+    /// number of people in a group -> number of groups with that size
+    pub group_sizes: BTreeMap<usize, usize>,
+}
+
+impl Statistics {
+    /// One `QuestionStats` per question of `alphabet`, in alphabet order
+    pub fn from_slice(input: &str, alphabet: &Alphabet) -> Result<Statistics, Vec<InvalidAnswer>> {
+        let groups = parse_groups(input, alphabet)?;
+
+        let questions = (0..alphabet.len())
+            .filter_map(|index| alphabet.question(index))
+            .map(|question| QuestionStats {
+                question,
+                groups: groups.iter().filter(|group| alphabet.contains(&group.union(), question)).count(),
+                people: groups
+                    .iter()
+                    .flat_map(|group| group.people())
+                    .filter(|person| alphabet.contains(person, question))
+                    .count(),
+            })
+            .collect();
+
+        let mut largest_group: Option<GroupSize> = None;
+        let mut smallest_group: Option<GroupSize> = None;
+        let mut group_sizes: BTreeMap<usize, usize> = BTreeMap::new();
+        for (index, group) in groups.iter().enumerate() {
+            let people = group.people().len();
+            if largest_group.as_ref().is_none_or(|largest| people > largest.people) {
+                largest_group = Some(GroupSize { index, people });
+            }
+            if smallest_group.as_ref().is_none_or(|smallest| people < smallest.people) {
+                smallest_group = Some(GroupSize { index, people });
+            }
+            *group_sizes.entry(people).or_insert(0) += 1;
+        }
+
+        Ok(Statistics { questions, largest_group, smallest_group, group_sizes })
+    }
+
+    /// `question,groups,people` header followed by one line per question
+    pub fn questions_csv(&self) -> String {
+        let mut csv = String::from("question,groups,people\n");
+        for stats in &self.questions {
+            csv.push_str(&format!("{},{},{}\n", stats.question, stats.groups, stats.people));
+        }
+        csv
+    }
+
+    /// `people,groups` header followed by one line per group size
+    pub fn group_sizes_csv(&self) -> String {
+        let mut csv = String::from("people,groups\n");
+        for (people, groups) in &self.group_sizes {
+            csv.push_str(&format!("{},{}\n", people, groups));
+        }
+        csv
+    }
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Parsed = Vec<Group>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Group>, String> {
+        parse_groups(input, &Alphabet::Lowercase).map_err(|errors| {
+            errors.iter().map(|error| error.to_string()).collect::<Vec<String>>().join(", ")
+        })
+    }
+
+    fn part1(&self, groups: &Vec<Group>) -> Result<String, String> {
+        Ok(groups.iter().map(|group| group.union().len()).sum::<usize>().to_string())
+    }
+
+    fn part2(&self, groups: &Vec<Group>) -> Result<String, String> {
+        Ok(groups.iter().map(|group| group.intersection().len()).sum::<usize>().to_string())
+    }
+}
+
+#[cfg(test)]
 mod day6_tests {
     use std::fs;
-    use crate::day6::{group_count, group_count_all_in_common, group_chunk};
+    use crate::day6::{group_count, group_count_all_in_common, group_chunk, Answers, Group, Statistics, QuestionStats,
+                      GroupSize, Alphabet, InvalidAnswer, validate_input, Day6};
+    use crate::solution::Solution;
 
     #[test]
     fn group_count_is_correct() {
@@ -83,29 +396,29 @@ mod day6_tests {
     fn multi_group_chunk_and_count_properly() {
         let input = "a\n\nabc\n\n123\n\ncd";
 
-        let count = group_chunk(input)
+        let count: usize = group_chunk(input)
             .iter()
             .map(|&line| group_count(line))
-            .fold(0, |v, acc| acc + v);
+            .sum();
 
         assert_eq!(count, 6);
     }
 
     #[test]
     fn check_if_input_only_contains_letters_a_to_z_and_new_lines() {
-        let data = fs::read_to_string("resources/day-6-input").unwrap();
-        let invalid_chars_found = data.chars().filter(|c| c.is_ascii_alphabetic() && c.is_whitespace()).count();
-        assert_eq!(0, invalid_chars_found)
+        let data = fs::read_to_string("resources/2020/day-6-input").unwrap();
+        let invalid_chars_found = validate_input(data.as_str(), &Alphabet::Lowercase);
+        assert_eq!(Vec::<InvalidAnswer>::new(), invalid_chars_found)
     }
 
     #[test]
     fn solve_part_1() {
-        let data = fs::read_to_string("resources/day-6-input").unwrap();
+        let data = fs::read_to_string("resources/2020/day-6-input").unwrap();
 
         let sum = group_chunk(data.as_str())
             .iter()
             .map(|&line| group_count(line))
-            .fold(0, |acc, x| acc + x);
+            .sum::<usize>();
 
         assert!(sum > 0);
         println!("Sum of positive answers in groups is {}", sum);
@@ -155,14 +468,134 @@ mod day6_tests {
 
     #[test]
     fn solve_part_2() {
-        let data = fs::read_to_string("resources/day-6-input").unwrap();
+        let data = fs::read_to_string("resources/2020/day-6-input").unwrap();
 
         let sum = group_chunk(data.as_str())
             .iter()
             .map(|&line| group_count_all_in_common(line))
-            .fold(0, |acc, x| acc + x);
+            .sum::<usize>();
 
         assert!(sum > 0);
         println!("Sum of positive _common_ answers in groups is {}", sum);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn group_commons_count_ignores_repeated_answers_from_the_same_person() {
+        let input_line = "aa\nb\n";
+        let count = group_count_all_in_common(input_line);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn answers_set_operations() {
+        let first = Answers::from_slice("abc");
+        let second = Answers::from_slice("bcd");
+        assert_eq!(vec!['a', 'b', 'c', 'd'], first.union(&second).questions());
+        assert_eq!(vec!['b', 'c'], first.intersection(&second).questions());
+        assert_eq!(vec!['a', 'd'], first.symmetric_difference(&second).questions());
+    }
+
+    #[test]
+    fn group_counts_answers_per_number_of_people() {
+        let group = Group::from_slice("abc\nab\nad\n");
+        assert_eq!(3, group.people().len());
+        assert_eq!(vec!['a', 'b', 'c', 'd'], group.union().questions());
+        assert_eq!(vec!['a'], group.intersection().questions());
+        assert_eq!(vec!['a', 'b'], group.at_least(2).questions());
+        assert_eq!(vec!['c', 'd'], group.exactly(1).questions());
+        assert_eq!(vec!['a', 'c', 'd'], group.symmetric_difference().questions());
+    }
+
+    #[test]
+    fn empty_group_has_no_answers() {
+        let group = Group::from_slice("");
+        assert!(group.union().is_empty());
+        assert!(group.intersection().is_empty());
+    }
+
+    #[test]
+    fn statistics_per_question() {
+        let statistics = Statistics::from_slice("abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n", &Alphabet::Lowercase).unwrap();
+
+        assert_eq!(QuestionStats { question: 'a', groups: 4, people: 8 }, statistics.questions[0]);
+        assert_eq!(QuestionStats { question: 'b', groups: 4, people: 4 }, statistics.questions[1]);
+        assert_eq!(QuestionStats { question: 'z', groups: 0, people: 0 }, statistics.questions[25]);
+        assert_eq!(Some(GroupSize { index: 3, people: 4 }), statistics.largest_group);
+        assert_eq!(Some(GroupSize { index: 0, people: 1 }), statistics.smallest_group);
+    }
+
+    #[test]
+    fn statistics_exported_as_csv() {
+        let statistics = Statistics::from_slice("ab\nb\n\nc\n\nab\n", &Alphabet::Lowercase).unwrap();
+
+        assert!(statistics.questions_csv().starts_with("question,groups,people\na,2,2\nb,2,3\nc,1,1\nd,0,0\n"));
+        assert_eq!(27, statistics.questions_csv().lines().count());
+        assert_eq!("people,groups\n1,2\n2,1\n", statistics.group_sizes_csv());
+    }
+
+    #[test]
+    fn statistics_for_input() {
+        let data = fs::read_to_string("resources/2020/day-6-input").unwrap();
+        let statistics = Statistics::from_slice(data.as_str(), &Alphabet::Lowercase).unwrap();
+
+        let nr_groups: usize = statistics.group_sizes.values().sum();
+        assert_eq!(group_chunk(data.as_str()).len(), nr_groups);
+        let sum: usize = statistics.questions.iter().map(|stats| stats.groups).sum();
+        assert_eq!(group_chunk(data.as_str()).iter().map(|&group| group_count(group)).sum::<usize>(), sum);
+        println!("{}", statistics.questions_csv());
+    }
+
+    #[test]
+    fn lowercase_alphabet_reports_invalid_characters() {
+        let errors = Group::parse("ab\naB1\n", &Alphabet::Lowercase).err().unwrap();
+        assert_eq!(
+            vec![
+                InvalidAnswer { line_number: 2, position: 1, character: 'B' },
+                InvalidAnswer { line_number: 2, position: 2, character: '1' },
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn case_insensitive_alphabet_merges_cases() {
+        let alphabet = Alphabet::CaseInsensitive;
+        let group = Group::parse("aB\nAb\n", &alphabet).unwrap();
+        assert_eq!(vec!['a', 'b'], alphabet.questions(&group.intersection()));
+    }
+
+    #[test]
+    fn custom_alphabet_with_unicode_questions() {
+        let alphabet = Alphabet::custom("αβγ✓").unwrap();
+        let group = Group::parse("αβ✓\nβ✓\n", &alphabet).unwrap();
+        assert_eq!(vec!['β', '✓'], alphabet.questions(&group.intersection()));
+        assert_eq!(vec!['α'], alphabet.questions(&group.exactly(1)));
+        assert!(Group::parse("a\n", &alphabet).is_err());
+
+        let statistics = Statistics::from_slice("αβ\nβ\n\nγ\n", &alphabet).unwrap();
+        assert_eq!(QuestionStats { question: 'β', groups: 1, people: 2 }, statistics.questions[1]);
+        assert_eq!(4, statistics.questions.len());
+    }
+
+    #[test]
+    fn custom_alphabet_is_limited_to_128_questions() {
+        let questions: String = (0..129).filter_map(|offset| char::from_u32(0x100 + offset)).collect();
+        assert!(Alphabet::custom(&questions).is_err());
+        assert!(Alphabet::custom(&questions[..questions.len() - 2]).is_ok());
+    }
+
+    #[test]
+    fn solver_refuses_characters_outside_the_alphabet() {
+        assert_eq!(
+            Err(String::from("line 4: 'B' at position 1 is not a question")),
+            Day6.parse("abc\n\na\naB\n").map(|groups| groups.len())
+        );
+        assert_eq!(Ok(2), Day6.parse("abc\n\na\nab\n").map(|groups| groups.len()));
+    }
+
+    #[test]
+    fn validator_reports_positions_in_the_whole_input() {
+        let errors = validate_input("abc\n\nab \nc\n", &Alphabet::Lowercase);
+        assert_eq!(vec![InvalidAnswer { line_number: 3, position: 2, character: ' ' }], errors);
+    }
+}
@@ -1,69 +1,589 @@
-mod day7 {
-    use std::borrow::BorrowMut;
-    use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use crate::parser::{Cursor, ParseError};
+use crate::solution::Solution;
 
-    pub struct Storage {
-        bags: HashMap<String, Bag>,
+/// Problem found when validating the rules ingested by a `Storage`
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleError {
+    /// bags that end up containing themselves, first and last names are the same
+    Cycle { path: Vec<String> },
+    /// bag that is referenced by other rules but has no rule of its own
+    UndefinedBag { name: String, referenced_by: Vec<String> },
+    /// bag with more than one rule, only the first one is kept. Lines start at 1
+    DuplicateRule { name: String, first_line: usize, line: usize },
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Cycle { path } => write!(f, "bags contain themselves: {}", path.join(" -> ")),
+            RuleError::UndefinedBag { name, referenced_by } =>
+                write!(f, "no rule for {} bags, used by {}", name, referenced_by.join(", ")),
+            RuleError::DuplicateRule { name, first_line, line } =>
+                write!(f, "line {}: {} bags already have a rule on line {}", line, name, first_line),
+        }
+    }
+}
+
+/// Why the number of bags inside another one could not be computed
+#[derive(Debug, PartialEq)]
+pub enum CountError {
+    UnknownBag { name: String },
+    /// bags that end up containing themselves, first and last names are the same
+    Cycle { path: Vec<String> },
+    /// the count for this bag does not fit in a `u128`
+    Overflow { name: String },
+}
+
+impl fmt::Display for CountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CountError::UnknownBag { name } => write!(f, "there is no rule for {} bags", name),
+            CountError::Cycle { path } => write!(f, "bags contain themselves: {}", path.join(" -> ")),
+            CountError::Overflow { name } => write!(f, "{} bags hold too many bags to count", name),
+        }
     }
+}
+
+/// A single line of the rule language:
+///
+/// ```text
+/// rule     := colour "bags" "contain" contents "."
+/// contents := "no" "other" "bags" | item ("," item)*
+/// item     := number colour ("bag" | "bags")
+/// colour   := word+
+/// ```
+///
+/// "bag" must be used when the number is 1 and "bags" otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub bag: String,
+    pub contents: Vec<(String, usize)>,
+}
 
-    impl Storage {
-        pub fn new() -> Self {
-            Storage {
-                bags: HashMap::new(),
+impl Rule {
+    pub fn parse(line: &str) -> Result<Rule, ParseError> {
+        let mut cursor = Cursor::new(line);
+        let bag = colour(&mut cursor)?;
+        cursor.expect_word("bags")?;
+        cursor.expect_word("contain")?;
+
+        let mut contents: Vec<(String, usize)> = Vec::new();
+        if cursor.peek_word() == Some("no") {
+            no_other_bags(&mut cursor)?;
+        } else {
+            for (name, count) in cursor.separated_list(',', item)? {
+                contents.push((name.to_string(), count));
             }
         }
+        cursor.expect_char('.')?;
+        cursor.expect_end()?;
+
+        Ok(Rule { bag: bag.to_string(), contents })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bags contain ", self.bag)?;
+        if self.contents.is_empty() {
+            return write!(f, "no other bags.");
+        }
+        let items: Vec<String> = self.contents
+            .iter()
+            .map(|(name, count)| format!("{} {} {}", count, name, if *count == 1 { "bag" } else { "bags" }))
+            .collect();
+        write!(f, "{}.", items.join(", "))
+    }
+}
+
+/// One or more words up to, but not including, "bag" or "bags"
+fn colour<'a>(cursor: &mut Cursor<'a>) -> Result<&'a str, ParseError> {
+    let start = cursor.position();
+    while let Some(word) = cursor.peek_word() {
+        if word == "bag" || word == "bags" {
+            break;
+        }
+        cursor.word()?;
+    }
+    if cursor.position() == start {
+        return Err(cursor.error("a colour"));
+    }
+    Ok(cursor.since(start))
+}
+
+fn no_other_bags(cursor: &mut Cursor) -> Result<(), ParseError> {
+    cursor.expect_word("no")?;
+    cursor.expect_word("other")?;
+    cursor.expect_word("bags")
+}
+
+fn item<'a>(cursor: &mut Cursor<'a>) -> Result<(&'a str, usize), ParseError> {
+    let start = *cursor;
+    let count = match cursor.number::<usize>() {
+        Ok(count) if count > 0 => count,
+        _ => return Err(start.error("a number of bags")),
+    };
+    let name = colour(cursor)?;
+    cursor.expect_word(if count == 1 { "bag" } else { "bags" })?;
+    Ok((name, count))
+}
+
+/// Index of a bag in its `Storage`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BagId(usize);
+
+/// Every bag seen in the rules, each one knows the bags it directly holds (forward)
+/// and the bags that directly hold it (reverse), both by id
+pub struct Storage {
+    ids: HashMap<String, BagId>,
+    bags: Vec<Bag>,
+    digested_lines: usize,
+    duplicate_rules: Vec<RuleError>,
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Storage::new()
+    }
+}
+
+impl Storage {
+    pub fn new() -> Self {
+        Storage {
+            ids: HashMap::new(),
+            bags: Vec::new(),
+            digested_lines: 0,
+            duplicate_rules: Vec::new(),
+        }
+    }
+
+    pub fn id(&self, bag_name: &str) -> Option<BagId> {
+        self.ids.get(bag_name).copied()
+    }
+
+    pub fn bag(&self, id: BagId) -> &Bag {
+        &self.bags[id.0]
+    }
+
+    pub fn get_bag(&self, bag_name: &str) -> &Bag {
+        self.bag(self.id(bag_name).unwrap())
+    }
+
+    /// Panics if the line is not a valid rule, see `try_digest`
+    pub fn digest(&mut self, line: &str) {
+        if let Err(error) = self.try_digest(line) {
+            panic!("invalid rule \"{}\": {}", line, error);
+        }
+    }
+
+    pub fn try_digest(&mut self, line: &str) -> Result<(), ParseError> {
+        self.digested_lines += 1;
+        let line_number = self.digested_lines;
+
+        let rule = Rule::parse(line)?;
+        let id = self.bag_entry(&rule.bag);
+
+        if let Some(first_line) = self.bags[id.0].defined_at {
+            self.duplicate_rules.push(RuleError::DuplicateRule {
+                name: rule.bag,
+                first_line,
+                line: line_number,
+            });
+            return Ok(());
+        }
+        self.bags[id.0].defined_at = Some(line_number);
+
+        for (inner_name, count) in &rule.contents {
+            let inner_id = self.bag_entry(inner_name);
+            self.bags[id.0].contains.push((inner_id, *count));
+            self.bags[inner_id.0].is_contained_by.push(id);
+        }
+        Ok(())
+    }
+
+    fn bag_entry(&mut self, bag_name: &str) -> BagId {
+        if let Some(id) = self.id(bag_name) {
+            return id;
+        }
+        let id = BagId(self.bags.len());
+        self.bags.push(Bag::new(id, bag_name.to_string()));
+        self.ids.insert(bag_name.to_string(), id);
+        id
+    }
 
-        pub fn get_bag(&self, bag_name: &str) -> &Bag {
-            self.bags.get(bag_name).unwrap()
+    /// Parses a single item of a rule such as "2 muted yellow bags", "no other bags" gives `("", 0)`.
+    /// Panics if the input is not a valid item
+    pub fn extract_bag_name_and_count(input: &str) -> (&str, usize) {
+        let mut cursor = Cursor::new(input);
+        let result = if cursor.peek_word() == Some("no") {
+            no_other_bags(&mut cursor).map(|_| ("", 0))
+        } else {
+            item(&mut cursor)
+        };
+        match result.and_then(|item| cursor.expect_end().map(|_| item)) {
+            Ok(item) => item,
+            Err(error) => panic!("invalid bag count \"{}\": {}", input, error),
         }
+    }
+
+    fn names(&self, ids: &[BagId]) -> Vec<String> {
+        ids.iter().map(|&id| self.bag(id).name.clone()).collect()
+    }
 
-        fn digest_line(&mut self, line: &str) {
-            let parts: Vec<&str> = line.replace('.', "").split(" bags contain ").collect();
+    /// Every bag id, sorted by bag name
+    fn sorted_ids(&self) -> Vec<BagId> {
+        let mut ids: Vec<BagId> = self.ids.values().copied().collect();
+        ids.sort_by(|&a, &b| self.bag(a).name.cmp(&self.bag(b).name));
+        ids
+    }
+
+    /// Checks the whole rule set, to be called once every line was digested
+    pub fn validate(&self) -> Result<(), Vec<RuleError>> {
+        let mut errors: Vec<RuleError> = Vec::new();
+        let ids = self.sorted_ids();
 
-            let &mut bag = self
-                .bags
-                .get(parts[0])
-                .get_or_insert(&Bag::new(parts[0].to_string()));
+        errors.extend(self.duplicate_rules.iter().cloned());
 
-            //bag.contains.
+        for &id in &ids {
+            let bag = self.bag(id);
+            if bag.defined_at.is_none() {
+                let mut referenced_by = self.names(&bag.is_contained_by);
+                referenced_by.sort();
+                errors.push(RuleError::UndefinedBag { name: bag.name.clone(), referenced_by });
+            }
         }
 
-        pub fn extract_bag_name_and_count(input: &str) -> (&str, usize) {
-            let (count_slice, name_slice) = input.trim().split_at(input.find(' ').unwrap());
-            return match count_slice {
-                "no" => ("", 0),
-                _ => {
-                    let count: usize = count_slice.parse().unwrap();
-                    let name: Vec<&str> = name_slice.split("bag").collect();
-                    return (name[0].trim(), count);
+        let mut visited: HashMap<BagId, Visit> = HashMap::new();
+        let mut path: Vec<BagId> = Vec::new();
+        for &id in &ids {
+            self.find_cycles(id, &mut visited, &mut path, &mut errors);
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Graphviz DOT graph with one node per bag and an edge, labelled with the count,
+    /// from each bag to the bags it directly holds
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(DotScope::All)
+    }
+
+    pub fn to_dot_with(&self, scope: DotScope) -> String {
+        let included: HashSet<BagId> = match scope {
+            DotScope::All => self.ids.values().copied().collect(),
+            DotScope::AncestorsOf(name) => self.reachable(name, |bag| bag.is_contained_by.clone()),
+            DotScope::DescendantsOf(name) => {
+                self.reachable(name, |bag| bag.contains.iter().map(|&(inner_id, _)| inner_id).collect())
+            }
+        };
+        let ids: Vec<BagId> = self.sorted_ids().into_iter().filter(|id| included.contains(id)).collect();
+
+        let mut dot = String::from("digraph bags {\n");
+        for &id in &ids {
+            dot.push_str(&format!("    {};\n", Storage::dot_id(&self.bag(id).name)));
+        }
+        for &id in &ids {
+            let mut inner_bags: Vec<(&str, usize)> = self.bag(id).contains.iter()
+                .filter(|(inner_id, _)| included.contains(inner_id))
+                .map(|&(inner_id, count)| (self.bag(inner_id).name.as_str(), count))
+                .collect();
+            inner_bags.sort();
+            for (inner_name, count) in inner_bags {
+                dot.push_str(&format!(
+                    "    {} -> {} [label={}];\n",
+                    Storage::dot_id(&self.bag(id).name),
+                    Storage::dot_id(inner_name),
+                    count
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn dot_id(name: &str) -> String {
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    /// The bag itself plus every bag found by repeatedly following `next`,
+    /// empty if the bag is unknown
+    fn reachable<F>(&self, name: &str, next: F) -> HashSet<BagId>
+        where F: Fn(&Bag) -> Vec<BagId> {
+        let mut found: HashSet<BagId> = HashSet::new();
+        let mut pending: Vec<BagId> = self.id(name).into_iter().collect();
+        while let Some(current) = pending.pop() {
+            if found.insert(current) {
+                pending.extend(next(self.bag(current)));
+            }
+        }
+        found
+    }
+
+    /// Every bag that can eventually hold the given one, sorted by name
+    pub fn holders(&self, name: &str) -> Vec<String> {
+        let mut holders: Vec<String> = self
+            .reachable(name, |bag| bag.is_contained_by.clone())
+            .into_iter()
+            .filter(|&id| self.bag(id).name != name)
+            .map(|id| self.bag(id).name.clone())
+            .collect();
+        holders.sort();
+        holders
+    }
+
+    /// Every chain of bags, from `outer` to `inner`, where each bag directly holds the next one
+    pub fn paths(&self, outer: &str, inner: &str) -> Vec<Vec<String>> {
+        let mut paths: Vec<Vec<String>> = Vec::new();
+        if let (Some(outer_id), Some(inner_id)) = (self.id(outer), self.id(inner)) {
+            let mut path: Vec<BagId> = vec![outer_id];
+            self.collect_paths(inner_id, &mut path, &mut paths);
+        }
+        paths
+    }
+
+    fn collect_paths(&self, inner_id: BagId, path: &mut Vec<BagId>, paths: &mut Vec<Vec<String>>) {
+        let current = *path.last().unwrap();
+        if current == inner_id {
+            paths.push(self.names(path));
+            return;
+        }
+        let mut next_ids: Vec<BagId> = self.bag(current).contains.iter().map(|&(id, _)| id).collect();
+        next_ids.sort_by(|&a, &b| self.bag(a).name.cmp(&self.bag(b).name));
+        for next_id in next_ids {
+            if !path.contains(&next_id) {
+                path.push(next_id);
+                self.collect_paths(inner_id, path, paths);
+                path.pop();
+            }
+        }
+    }
+
+    /// Shortest chain of bags, from `outer` to `inner`, where each bag directly holds the next one
+    pub fn shortest_chain(&self, outer: &str, inner: &str) -> Option<Vec<String>> {
+        let outer_id = self.id(outer)?;
+        let inner_id = self.id(inner)?;
+
+        let mut previous: HashMap<BagId, BagId> = HashMap::new();
+        let mut pending: VecDeque<BagId> = VecDeque::new();
+        pending.push_back(outer_id);
+        while let Some(current) = pending.pop_front() {
+            if current == inner_id {
+                let mut chain: Vec<BagId> = vec![current];
+                while let Some(&before) = previous.get(chain.last().unwrap()) {
+                    chain.push(before);
+                }
+                chain.reverse();
+                return Some(self.names(&chain));
+            }
+            for &(next_id, _) in &self.bag(current).contains {
+                if next_id != outer_id && !previous.contains_key(&next_id) {
+                    previous.insert(next_id, current);
+                    pending.push_back(next_id);
                 }
-            };
+            }
         }
+        None
     }
 
-    pub struct Bag {
-        name: String,
-        contains: HashMap<Bag, usize>,
-        is_contained_by: Vec<Bag>,
+    /// How many levels of bags are nested inside the given one, 0 for a bag that holds nothing
+    pub fn depth(&self, name: &str) -> Result<usize, CountError> {
+        let mut depths: HashMap<BagId, usize> = HashMap::new();
+        for id in self.topological_order(name)? {
+            let depth = self.bag(id).contains.iter().map(|(inner_id, _)| depths[inner_id] + 1).max().unwrap_or(0);
+            depths.insert(id, depth);
+        }
+        Ok(depths[&self.id(name).unwrap()])
+    }
+
+    /// How many bags end up inside the given one
+    pub fn total_contained(&self, name: &str) -> Result<u128, CountError> {
+        let mut subtotals: HashMap<BagId, u128> = HashMap::new();
+        for id in self.topological_order(name)? {
+            let mut subtotal: u128 = 0;
+            for (inner_id, count) in &self.bag(id).contains {
+                subtotal = subtotals[inner_id]
+                    .checked_add(1)
+                    .and_then(|inner_total| inner_total.checked_mul(*count as u128))
+                    .and_then(|inner_total| inner_total.checked_add(subtotal))
+                    .ok_or_else(|| CountError::Overflow { name: self.bag(id).name.clone() })?;
+            }
+            subtotals.insert(id, subtotal);
+        }
+        Ok(subtotals[&self.id(name).unwrap()])
     }
 
-    impl Bag {
-        fn new(name: String) -> Self {
-            Bag {
-                name,
-                contains: HashMap::new(),
-                is_contained_by: Vec::new(),
+    /// How many bags of each colour end up inside the given one
+    pub fn breakdown(&self, name: &str) -> Result<BTreeMap<String, u128>, CountError> {
+        let mut breakdowns: HashMap<BagId, BTreeMap<String, u128>> = HashMap::new();
+        for id in self.topological_order(name)? {
+            let overflow = || CountError::Overflow { name: self.bag(id).name.clone() };
+            let mut breakdown: BTreeMap<String, u128> = BTreeMap::new();
+            for (inner_id, count) in &self.bag(id).contains {
+                let count = *count as u128;
+                let direct = breakdown.entry(self.bag(*inner_id).name.clone()).or_insert(0);
+                *direct = direct.checked_add(count).ok_or_else(overflow)?;
+                for (nested_name, &nested_count) in &breakdowns[inner_id] {
+                    let nested = breakdown.entry(nested_name.clone()).or_insert(0);
+                    *nested = nested_count
+                        .checked_mul(count)
+                        .and_then(|total| total.checked_add(*nested))
+                        .ok_or_else(overflow)?;
+                }
             }
+            breakdowns.insert(id, breakdown);
+        }
+        Ok(breakdowns.remove(&self.id(name).unwrap()).unwrap())
+    }
+
+    /// The given bag and every bag inside it, inner bags always come first
+    fn topological_order(&self, name: &str) -> Result<Vec<BagId>, CountError> {
+        let id = self.id(name).ok_or_else(|| CountError::UnknownBag { name: name.to_string() })?;
+
+        let mut visited: HashMap<BagId, Visit> = HashMap::new();
+        let mut path: Vec<BagId> = Vec::new();
+        let mut errors: Vec<RuleError> = Vec::new();
+        self.find_cycles(id, &mut visited, &mut path, &mut errors);
+        if let Some(RuleError::Cycle { path }) = errors.into_iter().next() {
+            return Err(CountError::Cycle { path });
         }
 
-        pub fn is_contained_by_len(&self) -> usize {
-            return self.is_contained_by.len();
+        let mut order: Vec<BagId> = Vec::new();
+        let mut pending: Vec<(BagId, bool)> = vec![(id, false)];
+        let mut done: HashSet<BagId> = HashSet::new();
+        while let Some((current, inner_bags_done)) = pending.pop() {
+            if inner_bags_done {
+                if done.insert(current) {
+                    order.push(current);
+                }
+            } else if !done.contains(&current) {
+                pending.push((current, true));
+                pending.extend(self.bag(current).contains.iter().map(|&(inner_id, _)| (inner_id, false)));
+            }
         }
+        Ok(order)
     }
+
+    /// Depth first search, a bag found again while still on the path closes a cycle
+    fn find_cycles(&self, id: BagId, visited: &mut HashMap<BagId, Visit>,
+                   path: &mut Vec<BagId>, errors: &mut Vec<RuleError>) {
+        match visited.get(&id) {
+            Some(Visit::Done) => return,
+            Some(Visit::InProgress) => {
+                let start = path.iter().position(|&bag| bag == id).unwrap();
+                let mut cycle = self.names(&path[start..]);
+                cycle.push(self.bag(id).name.clone());
+                errors.push(RuleError::Cycle { path: cycle });
+                return;
+            }
+            None => {}
+        }
+
+        visited.insert(id, Visit::InProgress);
+        path.push(id);
+        let mut inner_ids: Vec<BagId> = self.bag(id).contains.iter().map(|&(inner_id, _)| inner_id).collect();
+        inner_ids.sort_by(|&a, &b| self.bag(a).name.cmp(&self.bag(b).name));
+        for inner_id in inner_ids {
+            self.find_cycles(inner_id, visited, path, errors);
+        }
+        path.pop();
+        visited.insert(id, Visit::Done);
+    }
+}
+
+/// Which part of the graph `Storage::to_dot_with` exports
+pub enum DotScope<'a> {
+    All,
+    /// the bag and every bag that can eventually hold it
+    AncestorsOf(&'a str),
+    /// the bag and every bag it eventually holds
+    DescendantsOf(&'a str),
+}
+
+enum Visit {
+    InProgress,
+    Done,
+}
+
+pub struct Bag {
+    id: BagId,
+    name: String,
+    contains: Vec<(BagId, usize)>,
+    is_contained_by: Vec<BagId>,
+    defined_at: Option<usize>,
 }
 
+impl Bag {
+    fn new(id: BagId, name: String) -> Self {
+        Bag {
+            id,
+            name,
+            contains: Vec::new(),
+            is_contained_by: Vec::new(),
+            defined_at: None,
+        }
+    }
+
+    pub fn id(&self) -> BagId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Bags directly inside this one, with how many of each
+    pub fn contains(&self) -> &[(BagId, usize)] {
+        &self.contains
+    }
+
+    /// Bags that directly hold this one
+    pub fn is_contained_by(&self) -> &[BagId] {
+        &self.is_contained_by
+    }
+
+    pub fn is_contained_by_len(&self) -> usize {
+        return self.is_contained_by.len();
+    }
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Parsed = Storage;
+
+    fn parse(&self, input: &str) -> Result<Storage, String> {
+        let mut storage = Storage::new();
+        for (index, line) in input.lines().enumerate() {
+            storage.try_digest(line).map_err(|error| format!("line {}: {}", index + 1, error))?;
+        }
+        storage.validate().map_err(|errors| {
+            errors.iter().map(|error| error.to_string()).collect::<Vec<String>>().join(", ")
+        })?;
+        Ok(storage)
+    }
+
+    fn part1(&self, storage: &Storage) -> Result<String, String> {
+        Ok(storage.holders("shiny gold").len().to_string())
+    }
+
+    fn part2(&self, storage: &Storage) -> Result<String, String> {
+        storage
+            .total_contained("shiny gold")
+            .map(|total| total.to_string())
+            .map_err(|error| error.to_string())
+    }
+}
+
+#[cfg(test)]
 mod day7_tests {
-    use crate::day7::{Bag, Storage};
+    use std::fs;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use crate::day7::{Bag, Day7, Storage, RuleError, DotScope, CountError, Rule};
+    use crate::parser::ParseError;
+    use crate::solution::Solution;
 
     #[test]
     fn contained_bag_name_and_count_is_found1() {
@@ -122,4 +642,335 @@ mod day7_tests {
         assert_eq!(0, faded_blue_bag.is_contained_by_len());
         assert_eq!(0, dotted_black_bag.is_contained_by_len());
     }
+
+    #[test]
+    fn valid_rules_have_no_errors() {
+        let mut storage = Storage::new();
+        storage.digest("light red bags contain 1 bright white bag, 2 muted yellow bags.");
+        storage.digest("bright white bags contain 1 shiny gold bag.");
+        storage.digest("muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.");
+        storage.digest("shiny gold bags contain no other bags.");
+        storage.digest("faded blue bags contain no other bags.");
+
+        assert_eq!(Ok(()), storage.validate());
+        assert_eq!(2, storage.get_bag("shiny gold").is_contained_by_len());
+    }
+
+    #[test]
+    fn cycle_is_reported_with_its_path() {
+        let mut storage = Storage::new();
+        storage.digest("dark red bags contain 1 light blue bag.");
+        storage.digest("light blue bags contain 2 vibrant plum bags.");
+        storage.digest("vibrant plum bags contain 1 dark red bag, 1 shiny gold bag.");
+        storage.digest("shiny gold bags contain 3 shiny gold bags.");
+
+        let errors = storage.validate().unwrap_err();
+        assert_eq!(
+            vec![
+                RuleError::Cycle { path: vec!["dark red".to_string(), "light blue".to_string(), "vibrant plum".to_string(), "dark red".to_string()] },
+                RuleError::Cycle { path: vec!["shiny gold".to_string(), "shiny gold".to_string()] },
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn solver_refuses_invalid_rules() {
+        let error = Day7
+            .parse("bright white bags contain 1 shiny gold bag.\nbright white bags contain no other bags.\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            "line 2: bright white bags already have a rule on line 1, no rule for shiny gold bags, used by bright white",
+            error
+        );
+        assert_eq!(
+            Some(String::from("bags contain themselves: faded blue -> faded blue")),
+            Day7.parse("faded blue bags contain 1 faded blue bag.").err()
+        );
+    }
+
+    #[test]
+    fn undefined_bags_and_duplicate_rules_are_reported() {
+        let mut storage = Storage::new();
+        storage.digest("bright white bags contain 1 shiny gold bag.");
+        storage.digest("muted yellow bags contain 2 shiny gold bags.");
+        storage.digest("bright white bags contain no other bags.");
+
+        let errors = storage.validate().unwrap_err();
+        assert_eq!(
+            vec![
+                RuleError::DuplicateRule { name: "bright white".to_string(), first_line: 1, line: 3 },
+                RuleError::UndefinedBag {
+                    name: "shiny gold".to_string(),
+                    referenced_by: vec!["bright white".to_string(), "muted yellow".to_string()],
+                },
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn input_is_valid() {
+        let data = fs::read_to_string("resources/2020/day-7-input").unwrap();
+        let mut storage = Storage::new();
+        data.lines().for_each(|line| storage.digest(line));
+
+        assert_eq!(Ok(()), storage.validate());
+    }
+
+    fn example_storage() -> Storage {
+        let mut storage = Storage::new();
+        storage.digest("light red bags contain 1 bright white bag, 2 muted yellow bags.");
+        storage.digest("bright white bags contain 1 shiny gold bag.");
+        storage.digest("muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.");
+        storage.digest("shiny gold bags contain 1 dark olive bag.");
+        storage.digest("dark olive bags contain no other bags.");
+        storage.digest("faded blue bags contain no other bags.");
+        storage
+    }
+
+    #[test]
+    fn whole_graph_is_exported_to_dot() {
+        let expected = "digraph bags {
+    \"bright white\";
+    \"dark olive\";
+    \"faded blue\";
+    \"light red\";
+    \"muted yellow\";
+    \"shiny gold\";
+    \"bright white\" -> \"shiny gold\" [label=1];
+    \"light red\" -> \"bright white\" [label=1];
+    \"light red\" -> \"muted yellow\" [label=2];
+    \"muted yellow\" -> \"faded blue\" [label=9];
+    \"muted yellow\" -> \"shiny gold\" [label=2];
+    \"shiny gold\" -> \"dark olive\" [label=1];
+}
+";
+        assert_eq!(expected, example_storage().to_dot());
+    }
+
+    #[test]
+    fn ancestors_of_shiny_gold_are_exported_to_dot() {
+        let dot = example_storage().to_dot_with(DotScope::AncestorsOf("shiny gold"));
+        assert!(dot.contains("\"light red\" -> \"muted yellow\" [label=2];"));
+        assert!(!dot.contains("faded blue"));
+        assert!(!dot.contains("dark olive"));
+    }
+
+    #[test]
+    fn descendants_of_shiny_gold_are_exported_to_dot() {
+        let dot = example_storage().to_dot_with(DotScope::DescendantsOf("shiny gold"));
+        assert_eq!("digraph bags {\n    \"dark olive\";\n    \"shiny gold\";\n    \"shiny gold\" -> \"dark olive\" [label=1];\n}\n", dot);
+    }
+
+    #[test]
+    fn total_contained_for_example() {
+        let mut storage = Storage::new();
+        storage.digest("shiny gold bags contain 2 dark red bags.");
+        storage.digest("dark red bags contain 2 dark orange bags.");
+        storage.digest("dark orange bags contain 2 dark yellow bags.");
+        storage.digest("dark yellow bags contain 2 dark green bags.");
+        storage.digest("dark green bags contain 2 dark blue bags.");
+        storage.digest("dark blue bags contain 2 dark violet bags.");
+        storage.digest("dark violet bags contain no other bags.");
+
+        assert_eq!(Ok(126), storage.total_contained("shiny gold"));
+        assert_eq!(Ok(0), storage.total_contained("dark violet"));
+    }
+
+    #[test]
+    fn breakdown_counts_every_inner_colour() {
+        let breakdown = example_storage().breakdown("light red").unwrap();
+
+        assert_eq!(
+            vec![("bright white", 1), ("dark olive", 5), ("faded blue", 18), ("muted yellow", 2), ("shiny gold", 5)],
+            breakdown.iter().map(|(name, &count)| (name.as_str(), count)).collect::<Vec<(&str, u128)>>()
+        );
+        assert_eq!(Ok(breakdown.values().sum()), example_storage().total_contained("light red"));
+    }
+
+    #[test]
+    fn total_contained_reports_cycles_and_unknown_bags() {
+        let mut storage = Storage::new();
+        storage.digest("dark red bags contain 1 light blue bag.");
+        storage.digest("light blue bags contain 2 dark red bags.");
+
+        assert_eq!(
+            Err(CountError::Cycle { path: vec!["dark red".to_string(), "light blue".to_string(), "dark red".to_string()] }),
+            storage.total_contained("dark red")
+        );
+        assert_eq!(
+            Err(CountError::UnknownBag { name: "shiny gold".to_string() }),
+            storage.total_contained("shiny gold")
+        );
+    }
+
+    #[test]
+    fn total_contained_reports_overflow() {
+        let mut storage = Storage::new();
+        for level in 0..5 {
+            storage.digest(&format!("level{} bags contain 18446744073709551615 level{} bags.", level, level + 1));
+        }
+        storage.digest("level5 bags contain no other bags.");
+
+        assert_eq!(Ok(u128::MAX - u64::MAX as u128), storage.total_contained("level3"));
+        assert_eq!(Err(CountError::Overflow { name: "level2".to_string() }), storage.total_contained("level0"));
+        assert_eq!("level2 bags hold too many bags to count", storage.total_contained("level0").unwrap_err().to_string());
+    }
+
+    #[test]
+    fn solve_part_2() {
+        let data = fs::read_to_string("resources/2020/day-7-input").unwrap();
+        let mut storage = Storage::new();
+        data.lines().for_each(|line| storage.digest(line));
+
+        let total = storage.total_contained("shiny gold").unwrap();
+        println!("A shiny gold bag holds {} other bags", total);
+        assert!(total > 0);
+    }
+
+    #[test]
+    fn rule_is_parsed() {
+        let rule = Rule::parse("light red bags contain 1 bright white bag, 2 muted yellow bags.").unwrap();
+        assert_eq!("light red", rule.bag);
+        assert_eq!(vec![("bright white".to_string(), 1), ("muted yellow".to_string(), 2)], rule.contents);
+
+        let rule = Rule::parse("faded blue bags contain no other bags.").unwrap();
+        assert!(rule.contents.is_empty());
+    }
+
+    #[test]
+    fn colour_names_may_contain_bag() {
+        let rule = Rule::parse("baggy red bags contain 3 handbag blue bags.").unwrap();
+        assert_eq!("baggy red", rule.bag);
+        assert_eq!(vec![("handbag blue".to_string(), 3)], rule.contents);
+        assert_eq!(("baggy red", 1), Storage::extract_bag_name_and_count("1 baggy red bag"));
+    }
+
+    #[test]
+    fn rule_errors_have_positions() {
+        assert_eq!(
+            Err(ParseError { position: 39, expected: "\"bag\"".to_string(), found: "\"bags\"".to_string() }),
+            Rule::parse("bright white bags contain 1 shiny gold bags.")
+        );
+        assert_eq!(
+            Err(ParseError { position: 43, expected: "\".\"".to_string(), found: "end of line".to_string() }),
+            Rule::parse("bright white bags contain 2 shiny gold bags")
+        );
+        assert_eq!(
+            Err(ParseError { position: 26, expected: "a number of bags".to_string(), found: "\"two\"".to_string() }),
+            Rule::parse("bright white bags contain two shiny gold bags.")
+        );
+        assert_eq!(
+            Err(ParseError { position: 0, expected: "a colour".to_string(), found: "\"bags\"".to_string() }),
+            Rule::parse("bags contain no other bags.")
+        );
+    }
+
+    #[test]
+    fn invalid_rule_is_not_digested() {
+        let mut storage = Storage::new();
+        assert!(storage.try_digest("bright white bags contain 1 shiny gold bag").is_err());
+        assert!(storage.try_digest("bright white bags contain 1 shiny gold bag.").is_ok());
+    }
+
+    #[test]
+    fn printed_rules_are_parsed_back_unchanged() {
+        let adjectives = ["light", "dark", "baggy", "shiny", "faded", "vibrant", "bagel"];
+        let colours = ["red", "gold", "blue", "handbag", "olive", "bagpipe", "plum"];
+        let mut rng = StdRng::seed_from_u64(2020);
+
+        for _ in 0..500 {
+            let colour = |rng: &mut StdRng| format!("{} {}", adjectives[rng.gen_range(0, 7)], colours[rng.gen_range(0, 7)]);
+            let rules: Vec<Rule> = (0..10)
+                .map(|_| Rule {
+                    bag: colour(&mut rng),
+                    contents: (0..rng.gen_range(0, 4)).map(|_| (colour(&mut rng), rng.gen_range(1, 20))).collect(),
+                })
+                .collect();
+
+            let printed: String = rules.iter().map(|rule| format!("{}\n", rule)).collect();
+            let parsed: Vec<Rule> = printed.lines().map(|line| Rule::parse(line).unwrap()).collect();
+            assert_eq!(rules, parsed, "rules printed as:\n{}", printed);
+        }
+    }
+
+    #[test]
+    fn input_rules_are_printed_unchanged() {
+        let data = fs::read_to_string("resources/2020/day-7-input").unwrap();
+        for line in data.lines() {
+            assert_eq!(line, Rule::parse(line).unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn forward_and_reverse_adjacency_share_ids() {
+        let storage = example_storage();
+        let shiny_gold = storage.get_bag("shiny gold");
+        let muted_yellow = storage.get_bag("muted yellow");
+
+        assert!(muted_yellow.contains().contains(&(shiny_gold.id(), 2)));
+        assert!(shiny_gold.is_contained_by().contains(&muted_yellow.id()));
+        assert_eq!("muted yellow", storage.bag(muted_yellow.id()).name());
+    }
+
+    #[test]
+    fn every_path_to_shiny_gold_is_found() {
+        let storage = example_storage();
+        assert_eq!(
+            vec![
+                vec!["light red".to_string(), "bright white".to_string(), "shiny gold".to_string()],
+                vec!["light red".to_string(), "muted yellow".to_string(), "shiny gold".to_string()],
+            ],
+            storage.paths("light red", "shiny gold")
+        );
+        assert!(storage.paths("shiny gold", "light red").is_empty());
+        assert!(storage.paths("light red", "unknown bag").is_empty());
+    }
+
+    #[test]
+    fn shortest_chain_to_shiny_gold() {
+        let mut storage = example_storage();
+        storage.digest("dotted black bags contain 1 light red bag, 4 dark olive bags.");
+
+        assert_eq!(
+            Some(vec!["dotted black".to_string(), "dark olive".to_string()]),
+            storage.shortest_chain("dotted black", "dark olive")
+        );
+        assert_eq!(4, storage.shortest_chain("dotted black", "shiny gold").unwrap().len());
+        assert_eq!(None, storage.shortest_chain("faded blue", "shiny gold"));
+    }
+
+    #[test]
+    fn holders_of_shiny_gold() {
+        let storage = example_storage();
+        assert_eq!(vec!["bright white", "light red", "muted yellow"], storage.holders("shiny gold"));
+        assert!(storage.holders("light red").is_empty());
+    }
+
+    #[test]
+    fn depth_is_the_longest_nesting() {
+        let storage = example_storage();
+        assert_eq!(Ok(3), storage.depth("light red"));
+        assert_eq!(Ok(1), storage.depth("shiny gold"));
+        assert_eq!(Ok(0), storage.depth("faded blue"));
+    }
+
+    #[test]
+    fn solve_part_1() {
+        let data = fs::read_to_string("resources/2020/day-7-input").unwrap();
+        let mut storage = Storage::new();
+        data.lines().for_each(|line| storage.digest(line));
+
+        let outer_bags: Vec<&str> = data.lines()
+            .map(|line| line.split(" bags contain ").next().unwrap())
+            .filter(|&name| name != "shiny gold" && storage.shortest_chain(name, "shiny gold").is_some())
+            .collect();
+        println!("{} bags can eventually hold a shiny gold bag", outer_bags.len());
+        assert!(!outer_bags.is_empty());
+        for &name in &outer_bags {
+            assert!(!storage.paths(name, "shiny gold").is_empty());
+        }
+    }
 }
@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::Path;
+
+use crate::answers::KnownAnswers;
+use crate::calendar::{Day, Year};
+use crate::input::ResourcesDir;
+use crate::registry::Registry;
+use crate::solution::Part;
+
+/// A worked example from the puzzle text. Examples live in `resources/<year>/examples/day-N/`
+/// as a `NAME.input` file next to a `NAME.answers` file such as:
+///
+/// ```text
+/// part1 = 514579
+/// part2 = 241861950
+/// ```
+///
+/// A part that is left out of the answers file is not checked.
+#[derive(Debug)]
+pub struct Example {
+    pub year: Year,
+    pub day: Day,
+    pub name: String,
+    pub input: String,
+    pub answers: KnownAnswers,
+}
+
+/// Every example of every year in `resources`, sorted by year, day and name
+pub fn discover(resources: &ResourcesDir) -> Result<Vec<Example>, String> {
+    let mut examples: Vec<Example> = Vec::new();
+    for year_dir in read_dir(resources.root())? {
+        let year = year_dir.file_name().and_then(|name| name.to_str()).and_then(|name| name.parse::<Year>().ok());
+        let examples_dir = year_dir.join("examples");
+        match year {
+            Some(year) if examples_dir.is_dir() => examples.extend(discover_year(year, &examples_dir)?),
+            _ => continue,
+        }
+    }
+    examples.sort_by(|a, b| (a.year, a.day, &a.name).cmp(&(b.year, b.day, &b.name)));
+    Ok(examples)
+}
+
+fn discover_year(year: Year, root: &Path) -> Result<Vec<Example>, String> {
+    let mut examples: Vec<Example> = Vec::new();
+    for day_dir in read_dir(root)? {
+        let day = day_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("day-"))
+            .and_then(|day| day.parse::<Day>().ok())
+            .ok_or_else(|| format!("{}: expected a day-N directory", day_dir.display()))?;
+
+        for input_path in read_dir(&day_dir)? {
+            if input_path.extension().and_then(|extension| extension.to_str()) != Some("input") {
+                continue;
+            }
+            let answers_path = input_path.with_extension("answers");
+            let read = |path: &Path| fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error));
+            let answers = KnownAnswers::parse(&format!("[{}.day{}]\n{}", year, day, read(&answers_path)?))
+                .map_err(|error| format!("{}: {}", answers_path.display(), error))?;
+
+            examples.push(Example {
+                year,
+                day,
+                name: input_path.file_stem().unwrap().to_string_lossy().to_string(),
+                input: read(&input_path)?,
+                answers,
+            });
+        }
+    }
+    Ok(examples)
+}
+
+fn read_dir(path: &Path) -> Result<Vec<std::path::PathBuf>, String> {
+    let entries = fs::read_dir(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    let mut paths: Vec<std::path::PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Runs the registered solver of the example's day, one message per part that
+/// did not give the expected answer
+pub fn check(registry: &Registry, example: &Example) -> Vec<String> {
+    let entry = match registry.get(example.year, example.day) {
+        Some(entry) => entry,
+        None => return vec![format!("{} day {} {}: day is not registered", example.year, example.day, example.name)],
+    };
+
+    Part::ALL
+        .iter()
+        .filter_map(|&part| {
+            let expected = example.answers.get(example.year, example.day, part)?;
+            match entry.puzzle.solve(&example.input, part) {
+                Ok(actual) if actual == expected => None,
+                Ok(actual) => Some(format!(
+                    "{} day {} {} {}: expected {} but got {}",
+                    example.year, example.day, example.name, part, expected, actual
+                )),
+                Err(error) => Some(format!("{} day {} {} {}: {}", example.year, example.day, example.name, part, error)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod examples_tests {
+    use crate::examples::{check, discover};
+    use crate::input::ResourcesDir;
+    use crate::registry::Registry;
+
+    #[test]
+    fn every_example_gives_the_expected_answers() {
+        let registry = Registry::default();
+        let examples = discover(&ResourcesDir::default()).unwrap();
+        let failures: Vec<String> = examples.iter().flat_map(|example| check(&registry, example)).collect();
+
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn every_registered_day_has_an_example() {
+        let examples = discover(&ResourcesDir::default()).unwrap();
+        for entry in Registry::default().entries() {
+            assert!(
+                examples.iter().any(|example| (example.year, example.day) == (entry.year, entry.day)),
+                "{} day {} has no example",
+                entry.year,
+                entry.day
+            );
+        }
+    }
+}
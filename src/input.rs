@@ -0,0 +1,245 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::calendar::{Day, Year};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Where the puzzle input of a day comes from
+pub trait InputProvider {
+    fn input(&self, year: Year, day: Day) -> Result<String, InputError>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InputError {
+    Io { path: String, error: String },
+    /// the server answered with something other than 200
+    Http { url: String, status: u16 },
+    /// the server could not be reached at all
+    Network { url: String, error: String },
+    /// `AOC_SESSION` is needed to fetch inputs
+    MissingSession,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io { path, error } => write!(f, "{}: {}", path, error),
+            InputError::Http { url, status } => write!(f, "{}: HTTP status {}", url, status),
+            InputError::Network { url, error } => write!(f, "{}: {}", url, error),
+            InputError::MissingSession => write!(f, "AOC_SESSION must hold the session cookie to fetch inputs"),
+        }
+    }
+}
+
+fn read_file(path: &PathBuf) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|error| InputError::Io { path: path.display().to_string(), error: error.to_string() })
+}
+
+/// `<year>/day-N-input` files in a directory, `resources` by default
+pub struct ResourcesDir {
+    root: PathBuf,
+}
+
+impl ResourcesDir {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        ResourcesDir { root: root.into() }
+    }
+
+    pub fn root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    pub fn path(&self, year: Year, day: Day) -> PathBuf {
+        self.root.join(year.to_string()).join(format!("day-{}-input", day))
+    }
+}
+
+impl Default for ResourcesDir {
+    fn default() -> Self {
+        ResourcesDir::new("resources")
+    }
+}
+
+impl InputProvider for ResourcesDir {
+    fn input(&self, year: Year, day: Day) -> Result<String, InputError> {
+        read_file(&self.path(year, day))
+    }
+}
+
+/// The same file whatever the year and day
+pub struct PathInput {
+    path: PathBuf,
+}
+
+impl PathInput {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        PathInput { path: path.into() }
+    }
+}
+
+impl InputProvider for PathInput {
+    fn input(&self, _year: Year, _day: Day) -> Result<String, InputError> {
+        read_file(&self.path)
+    }
+}
+
+/// Everything piped into the process, whatever the year and day. Standard input can only be
+/// read once, so it is read on the first call and every later day gets the same text
+#[derive(Default)]
+pub struct StdinInput {
+    text: Mutex<Option<String>>,
+}
+
+impl StdinInput {
+    pub fn new() -> Self {
+        StdinInput::default()
+    }
+}
+
+impl InputProvider for StdinInput {
+    fn input(&self, _year: Year, _day: Day) -> Result<String, InputError> {
+        let mut text = self.text.lock().unwrap();
+        if let Some(input) = text.as_ref() {
+            return Ok(input.clone());
+        }
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|error| InputError::Io { path: String::from("stdin"), error: error.to_string() })?;
+        *text = Some(input.clone());
+        Ok(input)
+    }
+}
+
+/// Downloads inputs from `<base_url>/<year>/day/<day>/input` with the session cookie of
+/// the logged in account. Every downloaded input is kept in `cache`, so it is only
+/// fetched once per machine
+pub struct HttpInput {
+    base_url: String,
+    session: String,
+    cache: ResourcesDir,
+}
+
+impl HttpInput {
+    pub fn new(base_url: &str, session: &str, cache: ResourcesDir) -> Self {
+        HttpInput {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            cache,
+        }
+    }
+
+    /// Reads `AOC_SESSION`, and optionally `AOC_BASE_URL`, caching into `resources`
+    pub fn from_env() -> Result<Self, InputError> {
+        let session = env::var("AOC_SESSION").map_err(|_| InputError::MissingSession)?;
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        Ok(HttpInput::new(&base_url, &session, ResourcesDir::default()))
+    }
+
+    pub fn url(&self, year: Year, day: Day) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, year, day)
+    }
+}
+
+impl InputProvider for HttpInput {
+    fn input(&self, year: Year, day: Day) -> Result<String, InputError> {
+        let cached = self.cache.path(year, day);
+        if cached.exists() {
+            return read_file(&cached);
+        }
+
+        let url = self.url(year, day);
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/SithEngineer/advent-of-code-2020")
+            .call();
+        let input = match response {
+            Ok(response) => response
+                .into_string()
+                .map_err(|error| InputError::Network { url: url.clone(), error: error.to_string() })?,
+            Err(ureq::Error::Status(status, _)) => return Err(InputError::Http { url, status }),
+            Err(error) => return Err(InputError::Network { url, error: error.to_string() }),
+        };
+
+        let io_error = |error: io::Error| InputError::Io { path: cached.display().to_string(), error: error.to_string() };
+        if let Some(parent) = cached.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        fs::write(&cached, &input).map_err(io_error)?;
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod input_tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use crate::calendar::{Day, Year};
+    use crate::input::{HttpInput, InputError, InputProvider, PathInput, ResourcesDir, StdinInput};
+    use crate::stub_server::StubServer;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn resources_dir_reads_day_files() {
+        let input = ResourcesDir::default().input(Year(2020), Day(3)).unwrap();
+        assert_eq!(323, input.lines().count());
+        assert!(matches!(ResourcesDir::default().input(Year(2020), Day(25)), Err(InputError::Io { .. })));
+        assert!(matches!(ResourcesDir::default().input(Year(2019), Day(3)), Err(InputError::Io { .. })));
+    }
+
+    #[test]
+    fn explicit_path_is_used_for_every_day() {
+        let provider = PathInput::new("resources/2020/examples/day-1/example.input");
+        assert_eq!(provider.input(Year(2020), Day(1)), provider.input(Year(2021), Day(7)));
+        assert!(provider.input(Year(2020), Day(1)).unwrap().starts_with("1721\n"));
+    }
+
+    #[test]
+    fn stdin_is_read_once_for_every_day() {
+        let provider = StdinInput { text: Mutex::new(Some(String::from("1721\n979\n"))) };
+        assert_eq!(Ok(String::from("1721\n979\n")), provider.input(Year(2020), Day(1)));
+        assert_eq!(Ok(String::from("1721\n979\n")), provider.input(Year(2020), Day(2)));
+    }
+
+    #[test]
+    fn http_input_is_fetched_once_and_cached() {
+        let server = StubServer::start(vec![(200, "1\n2\n3\n")]);
+        let cache = temp_dir("http-cache");
+        let provider = HttpInput::new(&server.url, "secret", ResourcesDir::new(&cache));
+
+        assert_eq!(Ok(String::from("1\n2\n3\n")), provider.input(Year(2020), Day(1)));
+        assert_eq!(Ok(String::from("1\n2\n3\n")), provider.input(Year(2020), Day(1)));
+        assert_eq!("1\n2\n3\n", fs::read_to_string(cache.join("2020/day-1-input")).unwrap());
+
+        let requests = server.requests();
+        assert_eq!(1, requests.len());
+        assert!(requests[0].starts_with("GET /2020/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].to_ascii_lowercase().contains("cookie: session=secret\r\n"));
+        fs::remove_dir_all(cache).unwrap();
+    }
+
+    #[test]
+    fn http_error_is_not_cached() {
+        let server = StubServer::start(vec![(404, "Please don't repeatedly request this endpoint")]);
+        let cache = temp_dir("http-error");
+        let provider = HttpInput::new(&server.url, "secret", ResourcesDir::new(&cache));
+
+        assert_eq!(
+            Err(InputError::Http { url: provider.url(Year(2021), Day(2)), status: 404 }),
+            provider.input(Year(2021), Day(2))
+        );
+        assert!(!cache.join("2021/day-2-input").exists());
+    }
+}
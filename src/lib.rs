@@ -0,0 +1,26 @@
+#![allow(clippy::needless_return)]
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+
+pub mod answers;
+pub mod bench;
+pub mod calendar;
+pub mod cancel;
+pub mod examples;
+pub mod input;
+pub mod manifest;
+pub mod parser;
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod submit;
+
+#[cfg(test)]
+mod stub_server;
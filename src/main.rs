@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+use advent_of_code_2020::answers::{self, KnownAnswers, Verdict};
+use advent_of_code_2020::bench::{self, BenchConfig, BenchResult};
+use advent_of_code_2020::calendar::{Day, Year};
+use advent_of_code_2020::input::{HttpInput, InputProvider, PathInput, ResourcesDir, StdinInput};
+use advent_of_code_2020::manifest;
+use advent_of_code_2020::registry::{Entry, Registry};
+use advent_of_code_2020::runner::{self, DayReport, RunConfig};
+use advent_of_code_2020::scaffold;
+use advent_of_code_2020::solution::Part;
+use advent_of_code_2020::submit::{self, History, Outcome, SubmitClient};
+
+const USAGE: &str = "usage:
+    aoc verify [day] [INPUT]
+    aoc bench [day] [INPUT] [--iterations N] [--warmup N] [--save FILE] [--compare FILE] [--threshold PERCENT]
+    aoc submit <day> <part> [INPUT] [--history FILE]
+    aoc inputs [day]
+    aoc new <day>
+    aoc all [INPUT] [--format table|json] [--workers N] [--timeout SECONDS]
+
+Every command takes --year YEAR, by default the latest year with a solved day, i.e. one
+with answers in answers.toml.
+
+INPUT defaults to the files in resources/YEAR/ and can be one of:
+    --input FILE    read every day from FILE, or from standard input when FILE is -
+    --fetch         download missing inputs into resources/YEAR/ using AOC_SESSION and AOC_BASE_URL
+
+submit posts the answer using AOC_SESSION and AOC_BASE_URL, every guess is kept in
+guess-history.tsv and answers already known to be wrong are not sent again
+
+all gives each part --timeout seconds, 60 by default and unlimited when 0, a part still
+running by then is reported as timed out and left behind
+
+inputs checks every resources/YEAR/day-N-input against day-N-input.manifest and tells
+whose input it is
+
+new creates the solver module, its input and example files and registers the day, it
+never overwrites existing files";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(|command| command.as_str()) {
+        Some("verify") => verify(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("submit") => submit(&args[1..]),
+        Some("inputs") => inputs(&args[1..]),
+        Some("all") => all(&args[1..]),
+        Some("new") => new(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        }
+    }
+}
+
+/// Options that do not take a value
+const FLAGS: [&str; 1] = ["fetch"];
+
+/// Splits `--name value` pairs and `--flag`s from the positional arguments
+fn parse_options(args: &[String]) -> Result<(Vec<String>, HashMap<String, String>), String> {
+    let mut positional: Vec<String> = Vec::new();
+    let mut options: HashMap<String, String> = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some(name) if FLAGS.contains(&name) => {
+                options.insert(name.to_string(), String::new());
+            }
+            Some(name) => {
+                let value = args.next().ok_or_else(|| format!("missing value for --{}", name))?;
+                options.insert(name.to_string(), value.to_string());
+            }
+            None => positional.push(arg.to_string()),
+        }
+    }
+    Ok((positional, options))
+}
+
+fn option<T: std::str::FromStr>(options: &HashMap<String, String>, name: &str, default: T) -> Result<T, String> {
+    match options.get(name) {
+        Some(value) => value.parse::<T>().map_err(|_| format!("invalid value for --{}: {}", name, value)),
+        None => Ok(default),
+    }
+}
+
+fn input_provider(options: &HashMap<String, String>) -> Result<Box<dyn InputProvider>, String> {
+    if options.contains_key("fetch") {
+        return Ok(Box::new(HttpInput::from_env().map_err(|error| error.to_string())?));
+    }
+    Ok(match options.get("input").map(|path| path.as_str()) {
+        Some("-") => Box::new(StdinInput::new()),
+        Some(path) => Box::new(PathInput::new(path)),
+        None => Box::new(ResourcesDir::default()),
+    })
+}
+
+fn parse_day(args: &[String]) -> Result<Option<Day>, String> {
+    match args {
+        [] => Ok(None),
+        [day] => day.parse::<Day>().map(Some),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+/// `--year`, by default the latest registered year with answers in `answers.toml`, so
+/// that days just scaffolded by `new` do not move the default. Without answers it is the
+/// latest registered year
+fn year(options: &HashMap<String, String>, registry: &Registry) -> Result<Year, String> {
+    let registered = registry.years();
+    let solved = KnownAnswers::load(answers::ANSWERS_PATH)
+        .map(|answers| answers.years())
+        .unwrap_or_default();
+    let latest = registered
+        .iter()
+        .rev()
+        .find(|year| solved.contains(year))
+        .or_else(|| registered.last())
+        .copied()
+        .unwrap_or(Year(2020));
+    option(options, "year", latest)
+}
+
+/// The registered days of `year`, or only `day` when given
+fn entries(registry: &Registry, year: Year, day: Option<Day>) -> Result<Vec<&Entry>, String> {
+    match day {
+        Some(day) => Ok(vec![registry.get(year, day).ok_or_else(|| format!("{} day {} is not registered", year, day))?]),
+        None => match registry.entries_of(year) {
+            entries if entries.is_empty() => Err(format!("no day of {} is registered", year)),
+            entries => Ok(entries),
+        },
+    }
+}
+
+/// Prints one line per part, `Ok(false)` when any answer regressed
+fn verify(args: &[String]) -> Result<bool, String> {
+    let (positional, options) = parse_options(args)?;
+    let day = parse_day(&positional)?;
+    let registry = Registry::default();
+    let year = year(&options, &registry)?;
+    let inputs = input_provider(&options)?;
+    let known_answers = KnownAnswers::load(answers::ANSWERS_PATH)?;
+    let verifications = answers::verify(&registry, &known_answers, year, day, inputs.as_ref())?;
+
+    for verification in &verifications {
+        let status = match &verification.verdict {
+            Verdict::Correct => {
+                let answer = known_answers.get(verification.year, verification.day, verification.part).unwrap();
+                format!("ok ({})", answer)
+            }
+            Verdict::Wrong { expected, actual } => format!("WRONG expected {} but got {}", expected, actual),
+            Verdict::Unknown { actual } => format!("unknown, got {}", actual),
+            Verdict::Failed { error } => format!("FAILED {}", error),
+        };
+        println!("{} day {} {}: {}", verification.year, verification.day, verification.part, status);
+    }
+
+    Ok(!verifications.iter().any(|verification| verification.is_regression()))
+}
+
+/// Prints a timing table, `Ok(false)` when compared with a baseline and something got slower
+fn bench(args: &[String]) -> Result<bool, String> {
+    let (positional, options) = parse_options(args)?;
+    let day = parse_day(&positional)?;
+    let defaults = BenchConfig::default();
+    let config = BenchConfig {
+        warmup: option(&options, "warmup", defaults.warmup)?,
+        iterations: option(&options, "iterations", defaults.iterations)?,
+    };
+    let threshold: f64 = option(&options, "threshold", 10.0)? / 100.0;
+    let inputs = input_provider(&options)?;
+
+    let registry = Registry::default();
+    let entries = entries(&registry, year(&options, &registry)?, day)?;
+
+    let mut results: Vec<BenchResult> = Vec::new();
+    for entry in entries {
+        let input = inputs.input(entry.year, entry.day).map_err(|error| error.to_string())?;
+        results.extend(bench::bench_day(entry, &input, &config)?);
+    }
+    print!("{}", bench::render_table(&results));
+
+    if let Some(path) = options.get("save") {
+        fs::write(path, bench::to_json(&results)).map_err(|error| format!("{}: {}", path, error))?;
+        println!("baseline saved to {}", path);
+    }
+
+    if let Some(path) = options.get("compare") {
+        let json = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+        let baseline = bench::parse_baseline(&json).map_err(|error| format!("{}: {}", path, error))?;
+        let slowdowns = bench::compare(&results, &baseline, threshold);
+        for slowdown in &slowdowns {
+            println!(
+                "SLOWER {}: {:?} -> {:?} (+{:.0}%)",
+                slowdown.key,
+                slowdown.baseline,
+                slowdown.current,
+                slowdown.ratio() * 100.0
+            );
+        }
+        return Ok(slowdowns.is_empty());
+    }
+    Ok(true)
+}
+
+/// Solves one part and posts the answer, `Ok(false)` unless it was correct
+fn submit(args: &[String]) -> Result<bool, String> {
+    let (positional, options) = parse_options(args)?;
+    let (day, part) = match positional.as_slice() {
+        [day, part] => (
+            day.parse::<Day>()?,
+            part.parse::<u8>().ok().and_then(Part::from_number).ok_or_else(|| format!("invalid part {}", part))?,
+        ),
+        _ => return Err(USAGE.to_string()),
+    };
+    let inputs = input_provider(&options)?;
+    let registry = Registry::default();
+    let year = year(&options, &registry)?;
+    let entry = registry.get(year, day).ok_or_else(|| format!("{} day {} is not registered", year, day))?;
+
+    let input = inputs.input(year, day).map_err(|error| error.to_string())?;
+    let answer = entry.puzzle.solve(&input, part)?;
+    let mut history = History::load(options.get("history").map_or(submit::HISTORY_PATH, |path| path.as_str()))?;
+    let client = SubmitClient::from_env().map_err(|error| error.to_string())?;
+    let outcome = client.submit(&mut history, year, day, part, &answer).map_err(|error| error.to_string())?;
+
+    println!("{} day {} {}: {} is {}", year, day, part, answer, outcome);
+    Ok(outcome == Outcome::Correct)
+}
+
+/// Prints whose input each day is, `Ok(false)` when an input does not match its manifest
+fn inputs(args: &[String]) -> Result<bool, String> {
+    let (positional, options) = parse_options(args)?;
+    let day = parse_day(&positional)?;
+    let registry = Registry::default();
+    let entries = entries(&registry, year(&options, &registry)?, day)?;
+    let resources = ResourcesDir::default();
+
+    let mut all_valid = true;
+    for entry in entries {
+        let report = manifest::validate(&resources, entry.year, entry.day)?;
+        println!("{} day {}: {}", entry.year, entry.day, report);
+        all_valid &= report.is_valid();
+    }
+    Ok(all_valid)
+}
+
+/// Runs every day in parallel and prints a summary, `Ok(false)` when any part failed
+fn all(args: &[String]) -> Result<bool, String> {
+    let (positional, options) = parse_options(args)?;
+    if !positional.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let defaults = RunConfig::default();
+    let timeout: f64 = option(&options, "timeout", defaults.timeout.map_or(0.0, |timeout| timeout.as_secs_f64()))?;
+    if !(timeout >= 0.0 && timeout.is_finite()) {
+        return Err(format!("invalid value for --timeout: {}", timeout));
+    }
+    let config = RunConfig {
+        workers: option(&options, "workers", defaults.workers)?,
+        timeout: Some(Duration::from_secs_f64(timeout)).filter(|timeout| !timeout.is_zero()),
+    };
+    let render: fn(&[DayReport]) -> String = match options.get("format").map_or("table", |format| format.as_str()) {
+        "table" => runner::render_table,
+        "json" => runner::to_json,
+        format => return Err(format!("invalid value for --format: {}", format)),
+    };
+    let inputs = input_provider(&options)?;
+
+    let registry = Registry::default();
+    let entries = entries(&registry, year(&options, &registry)?, None)?;
+    let reports = runner::run_all(&entries, inputs.as_ref(), &config);
+    print!("{}", render(&reports));
+    Ok(reports.iter().all(|report| report.is_success()))
+}
+
+/// Scaffolds a new day in the current directory
+fn new(args: &[String]) -> Result<bool, String> {
+    let (positional, options) = parse_options(args)?;
+    let day = parse_day(&positional)?.ok_or_else(|| USAGE.to_string())?;
+    let year = year(&options, &Registry::default())?;
+    for path in scaffold::new_day(Path::new("."), year, day)? {
+        println!("wrote {}", path.display());
+    }
+    println!(
+        "next: fill in resources/{0}/day-{1}-input and its manifest, the example answers, and answers.toml, \
+         then remove the #[ignore] of verify_{0}_day{1} in src/answers.rs",
+        year, day
+    );
+    Ok(true)
+}
@@ -0,0 +1,460 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::calendar::{Day, Year};
+use crate::input::ResourcesDir;
+
+/// How an input is split into records
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Separator {
+    /// every line is a record
+    Line,
+    /// records are groups of lines separated by an empty line
+    BlankLine,
+}
+
+impl Separator {
+    fn from_key(key: &str) -> Option<Separator> {
+        match key {
+            "line" => Some(Separator::Line),
+            "blank-line" => Some(Separator::BlankLine),
+            _ => None,
+        }
+    }
+
+    pub fn records<'a>(&self, data: &'a str) -> Vec<&'a str> {
+        let data = data.trim_end_matches('\n');
+        if data.is_empty() {
+            return Vec::new();
+        }
+        match self {
+            Separator::Line => data.split('\n').collect(),
+            Separator::BlankLine => data.split("\n\n").collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Literal(char),
+    /// `{number}`, one or more digits
+    Number,
+    /// `{letter}`, a single lowercase letter
+    Letter,
+    /// `{letters}`, one or more lowercase letters
+    Letters,
+    /// `{chars:.#}`, one or more of the listed characters, or exactly `n` of them
+    /// with `{chars:.#:n}`
+    Chars(Vec<char>, Option<usize>),
+    /// `{fields}`, one or more `key:value` pairs separated by a space, where the key is
+    /// lowercase letters and the value anything up to the next space
+    Fields,
+    /// `{any}`, the non empty rest of the line
+    Any,
+}
+
+/// What every line of an input looks like, written as literal text with
+/// placeholders, e.g. `{number}-{number} {letter}: {letters}` or `{chars:FB:7}{chars:LR:3}`. Placeholders are
+/// greedy and never backtrack, so a placeholder followed by characters it also
+/// accepts will swallow them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grammar {
+    pieces: Vec<Piece>,
+}
+
+impl Grammar {
+    pub fn parse(pattern: &str) -> Result<Grammar, String> {
+        let mut pieces: Vec<Piece> = Vec::new();
+        let mut rest = pattern;
+        while let Some(c) = rest.chars().next() {
+            if pieces.last() == Some(&Piece::Any) {
+                return Err(String::from("{any} must be the last piece"));
+            }
+            if c != '{' {
+                pieces.push(Piece::Literal(c));
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+            let end = rest.find('}').ok_or_else(|| format!("unclosed placeholder in {}", pattern))?;
+            let piece = match &rest[1..end] {
+                "number" => Piece::Number,
+                "letter" => Piece::Letter,
+                "letters" => Piece::Letters,
+                "fields" => Piece::Fields,
+                "any" => Piece::Any,
+                other => match other.strip_prefix("chars:") {
+                    Some(chars) => Grammar::chars(chars).ok_or_else(|| format!("invalid placeholder {{{}}}", other))?,
+                    None => return Err(format!("unknown placeholder {{{}}}", other)),
+                },
+            };
+            pieces.push(piece);
+            rest = &rest[end + 1..];
+        }
+        Ok(Grammar { pieces })
+    }
+
+    /// The piece of `{chars:...}`, a trailing `:n` fixes its length
+    fn chars(spec: &str) -> Option<Piece> {
+        let (chars, length) = match spec.rsplit_once(':') {
+            Some((chars, length)) if !chars.is_empty() && length.chars().all(|c| c.is_ascii_digit()) => {
+                (chars, Some(length.parse().ok().filter(|&length| length > 0)?))
+            }
+            _ => (spec, None),
+        };
+        if chars.is_empty() {
+            return None;
+        }
+        Some(Piece::Chars(chars.chars().collect(), length))
+    }
+
+    /// Length in bytes of the `key:value` pairs starting `text`, 0 unless every space
+    /// it reaches is followed by another pair
+    fn fields_length(text: &str) -> usize {
+        let mut length = 0;
+        loop {
+            let field = &text[length..];
+            let key = field.find(|c: char| !c.is_ascii_lowercase()).unwrap_or(field.len());
+            if key == 0 || !field[key..].starts_with(':') {
+                return 0;
+            }
+            let value = field[key + 1..].find(' ').unwrap_or(field.len() - key - 1);
+            if value == 0 {
+                return 0;
+            }
+            length += key + 1 + value;
+            if !text[length..].starts_with(' ') {
+                return length;
+            }
+            length += 1;
+        }
+    }
+
+    pub fn matches(&self, line: &str) -> bool {
+        let mut rest = line;
+        for piece in &self.pieces {
+            let accepts = |c: char| match piece {
+                Piece::Number => c.is_ascii_digit(),
+                Piece::Letter | Piece::Letters => c.is_ascii_lowercase(),
+                Piece::Chars(chars, _) => chars.contains(&c),
+                Piece::Literal(_) | Piece::Fields | Piece::Any => true,
+            };
+            let taken = match piece {
+                Piece::Literal(literal) => rest.chars().next().filter(|c| c == literal).map_or(0, char::len_utf8),
+                Piece::Letter => rest.chars().next().filter(|&c| accepts(c)).map_or(0, char::len_utf8),
+                Piece::Chars(_, Some(length)) => match rest.char_indices().nth(*length - 1) {
+                    Some((start, last)) if rest[..start].chars().all(accepts) && accepts(last) => start + last.len_utf8(),
+                    _ => 0,
+                },
+                Piece::Fields => Grammar::fields_length(rest),
+                Piece::Any => rest.len(),
+                _ => rest.find(|c: char| !accepts(c)).unwrap_or(rest.len()),
+            };
+            if taken == 0 {
+                return false;
+            }
+            rest = &rest[taken..];
+        }
+        rest.is_empty()
+    }
+}
+
+/// Describes one puzzle input: its format, how many records it has and whose it is.
+/// Stored next to the input as `day-N-input.manifest`:
+///
+/// ```toml
+/// account = "SithEngineer"
+/// separator = "line"
+/// grammar = "{number}"
+/// count = 200
+/// sha256 = "fc02dda2..."
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest {
+    pub account: String,
+    pub separator: Separator,
+    pub grammar: Grammar,
+    pub count: usize,
+    pub sha256: String,
+}
+
+impl Manifest {
+    /// The manifest that describes the input at `input`
+    pub fn path_for(input: &Path) -> PathBuf {
+        let mut path = input.as_os_str().to_owned();
+        path.push(".manifest");
+        PathBuf::from(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Manifest, String> {
+        let data = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        Manifest::parse(&data).map_err(|error| format!("{}: {}", path.display(), error))
+    }
+
+    pub fn parse(data: &str) -> Result<Manifest, String> {
+        let mut account: Option<String> = None;
+        let mut separator: Option<Separator> = None;
+        let mut grammar: Option<Grammar> = None;
+        let mut count: Option<usize> = None;
+        let mut sha256: Option<String> = None;
+
+        for (index, line) in data.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected key = value but found {}", line_number, line))?;
+            let value = value.trim();
+            let value = value.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')).unwrap_or(value);
+            let invalid = || format!("line {}: invalid {} {}", line_number, key.trim(), value);
+            match key.trim() {
+                "account" => account = Some(value.to_string()),
+                "separator" => separator = Some(Separator::from_key(value).ok_or_else(invalid)?),
+                "grammar" => grammar = Some(Grammar::parse(value).map_err(|error| format!("line {}: {}", line_number, error))?),
+                "count" => count = Some(value.parse().map_err(|_| invalid())?),
+                "sha256" if value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()) => {
+                    sha256 = Some(value.to_ascii_lowercase())
+                }
+                "sha256" => return Err(invalid()),
+                other => return Err(format!("line {}: unknown key {}", line_number, other)),
+            }
+        }
+
+        let missing = |key: &str| format!("missing {}", key);
+        Ok(Manifest {
+            account: account.ok_or_else(|| missing("account"))?,
+            separator: separator.ok_or_else(|| missing("separator"))?,
+            grammar: grammar.ok_or_else(|| missing("grammar"))?,
+            count: count.ok_or_else(|| missing("count"))?,
+            sha256: sha256.ok_or_else(|| missing("sha256"))?,
+        })
+    }
+
+    /// Checks the format of `data` against the manifest. The checksum and the record
+    /// count only tell whose input it is: an input of another account has the same
+    /// format but not necessarily as many records, and it is still valid
+    pub fn check(&self, data: &str) -> Report {
+        let sha256 = checksum(data);
+        let is_own_input = sha256 == self.sha256;
+        let records = self.separator.records(data);
+
+        let mut problems: Vec<Problem> = Vec::new();
+        let mut line_number = 1;
+        for (index, record) in records.iter().enumerate() {
+            for line in record.split('\n') {
+                if !self.grammar.matches(line) {
+                    problems.push(Problem::LineMismatch { record: index + 1, line_number, line: line.to_string() });
+                }
+                line_number += 1;
+            }
+            if self.separator == Separator::BlankLine {
+                line_number += 1;
+            }
+        }
+
+        Report {
+            account: self.account.clone(),
+            is_own_input,
+            sha256,
+            records: records.len(),
+            expected_records: self.count,
+            problems,
+        }
+    }
+}
+
+/// Lowercase hex SHA-256 of an input
+pub fn checksum(data: &str) -> String {
+    Sha256::digest(data.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    /// `line_number` is 1-based and counts every line of the input
+    LineMismatch { record: usize, line_number: usize, line: String },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::LineMismatch { record, line_number, line } => {
+                write!(f, "line {} (record {}) does not match the grammar: {}", line_number, record, line)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    /// the account named in the manifest
+    pub account: String,
+    /// whether the checksum matches, i.e. the input belongs to `account`
+    pub is_own_input: bool,
+    pub sha256: String,
+    pub records: usize,
+    /// how many records the input of `account` has
+    pub expected_records: usize,
+    /// lines that do not match the format, the only thing that makes an input invalid
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_own_input {
+            write!(
+                f,
+                "{} records, not the input of {} which has {} (sha256 {})",
+                self.records, self.account, self.expected_records, self.sha256
+            )?;
+        } else if self.records != self.expected_records {
+            write!(f, "{} records, input of {} but its manifest says {}", self.records, self.account, self.expected_records)?;
+        } else {
+            write!(f, "{} records, input of {}", self.records, self.account)?;
+        }
+        for problem in &self.problems {
+            write!(f, "\n    {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks `<year>/day-N-input` of `resources` against the manifest next to it
+pub fn validate(resources: &ResourcesDir, year: Year, day: Day) -> Result<Report, String> {
+    let path = resources.path(year, day);
+    let manifest = Manifest::load(&Manifest::path_for(&path))?;
+    let data = fs::read_to_string(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    Ok(manifest.check(&data))
+}
+
+#[cfg(test)]
+mod manifest_tests {
+    use std::path::Path;
+    use crate::calendar::{Day, Year};
+    use crate::input::ResourcesDir;
+    use crate::manifest::{checksum, validate, Grammar, Manifest, Problem, Separator};
+    use crate::registry::Registry;
+
+    #[test]
+    fn grammar_placeholders_match() {
+        let grammar = Grammar::parse("{number}-{number} {letter}: {letters}").unwrap();
+        assert!(grammar.matches("1-3 a: abcde"));
+        assert!(!grammar.matches("1-3 ab: abcde"));
+        assert!(!grammar.matches("1-3 a: "));
+        assert!(!grammar.matches("1-3 a: abcde!"));
+
+        let grammar = Grammar::parse("{chars:.#}").unwrap();
+        assert!(grammar.matches("..##.#"));
+        assert!(!grammar.matches("..O#"));
+        assert!(!grammar.matches(""));
+
+        let grammar = Grammar::parse("{chars:FB:7}{chars:LR:3}").unwrap();
+        assert!(grammar.matches("FBFBBFFRLR"));
+        assert!(!grammar.matches("FBFBBFRLR"));
+        assert!(!grammar.matches("FBFBBFFFRLR"));
+        assert!(!grammar.matches("FBFBBFFRL"));
+
+        let grammar = Grammar::parse("{fields}").unwrap();
+        assert!(grammar.matches("hgt:186cm byr:1939 hcl:#602927"));
+        assert!(grammar.matches("ecl:brn"));
+        assert!(!grammar.matches("ecl:brn "));
+        assert!(!grammar.matches("ecl: brn"));
+        assert!(!grammar.matches("ecl:brn byr"));
+        assert!(!grammar.matches("Ecl:brn"));
+
+        let grammar = Grammar::parse("{letters} bags contain {any}").unwrap();
+        assert!(grammar.matches("gold bags contain no other bags."));
+        assert!(!grammar.matches("gold bags contain "));
+    }
+
+    #[test]
+    fn invalid_grammar_is_rejected() {
+        assert!(Grammar::parse("{number").is_err());
+        assert!(Grammar::parse("{float}").is_err());
+        assert!(Grammar::parse("{chars:}").is_err());
+        assert!(Grammar::parse("{chars:FB:0}").is_err());
+        assert!(Grammar::parse("{any} {number}").is_err());
+    }
+
+    #[test]
+    fn records_are_split() {
+        assert_eq!(vec!["a", "b"], Separator::Line.records("a\nb\n"));
+        assert_eq!(vec!["a\nb", "c"], Separator::BlankLine.records("a\nb\n\nc\n"));
+        assert!(Separator::Line.records("").is_empty());
+    }
+
+    #[test]
+    fn manifest_is_parsed() {
+        let data = "account = \"someone\"\nseparator = \"line\"\ngrammar = \"{number}\"\ncount = 3\n\
+                    sha256 = \"7ce0a5a2f6f5d0a5ee1acd06e8ab7d39ad8d02a8f64fbb8d62f0e8e1e7dac29c\"\n";
+        let manifest = Manifest::parse(data).unwrap();
+        assert_eq!("someone", manifest.account);
+        assert_eq!(Separator::Line, manifest.separator);
+        assert_eq!(3, manifest.count);
+
+        assert!(Manifest::parse("account = \"someone\"").is_err());
+        assert!(Manifest::parse(&data.replace("\"line\"", "\"comma\"")).is_err());
+        assert!(Manifest::parse(&data.replace("count = 3", "count = many")).is_err());
+        assert!(Manifest::parse(&format!("{}colour = blue\n", data)).is_err());
+    }
+
+    #[test]
+    fn other_account_is_told_apart() {
+        let mut manifest = Manifest::parse(
+            "account = someone\nseparator = line\ngrammar = {number}\ncount = 3\nsha256 = 0000000000000000000000000000000000000000000000000000000000000000\n",
+        ).unwrap();
+
+        let report = manifest.check("1\n2\n");
+        assert!(!report.is_own_input);
+        assert!(report.is_valid());
+        assert_eq!(
+            format!("2 records, not the input of someone which has 3 (sha256 {})", checksum("1\n2\n")),
+            report.to_string()
+        );
+
+        let report = manifest.check("1\nx\n");
+        assert!(!report.is_valid());
+        assert_eq!(
+            vec![Problem::LineMismatch { record: 2, line_number: 2, line: "x".to_string() }],
+            report.problems
+        );
+
+        manifest.sha256 = checksum("1\n2\n3\n");
+        let report = manifest.check("1\n2\n3\n");
+        assert!(report.is_own_input);
+        assert!(report.is_valid());
+        assert_eq!("3 records, input of someone", report.to_string());
+    }
+
+    #[test]
+    fn every_registered_day_has_a_manifest() {
+        let resources = ResourcesDir::default();
+        for entry in Registry::default().entries() {
+            let path = Manifest::path_for(&resources.path(entry.year, entry.day));
+            assert!(path.exists(), "{}", path.display());
+        }
+        assert_eq!(
+            Path::new("resources/2020/day-1-input.manifest"),
+            Manifest::path_for(&resources.path(Year(2020), Day(1)))
+        );
+    }
+
+    #[test]
+    fn resources_match_their_manifests() {
+        let resources = ResourcesDir::default();
+        for entry in Registry::default().entries() {
+            let report = validate(&resources, entry.year, entry.day).unwrap();
+            assert!(report.is_valid(), "{} day {}: {}", entry.year, entry.day, report);
+        }
+    }
+}
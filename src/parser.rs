@@ -0,0 +1,237 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// What a `Cursor` expected and what it found instead, `position` is the byte offset
+/// of the offending token in the parsed text
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {} but found {} at position {}", self.expected, self.found, self.position)
+    }
+}
+
+/// Reads tokens off a `&str` from left to right. Whitespace between tokens is always
+/// skipped, so the cursor rests either on the start of a token or at the end. A token
+/// is a single punctuation character or a run of anything else up to whitespace or
+/// punctuation. Nothing is consumed when a read fails, and copies of a cursor can be
+/// kept to report an error at an earlier token
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        let mut cursor = Cursor { input, position: 0 };
+        cursor.skip_whitespace();
+        cursor
+    }
+
+    /// Byte offset of the next token
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    /// Text read since `start`, without the whitespace that followed it
+    pub fn since(&self, start: usize) -> &'a str {
+        self.input[start..self.position].trim_end()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Moves past `length` bytes and the whitespace after them
+    fn advance(&mut self, length: usize) {
+        self.position += length;
+        self.skip_whitespace();
+    }
+
+    /// Length in bytes of the run starting the rest that stops at whitespace or `stop`
+    fn run_length<F: Fn(char) -> bool>(&self, stop: F) -> usize {
+        let rest = self.rest();
+        rest.find(|c: char| c.is_whitespace() || stop(c)).unwrap_or(rest.len())
+    }
+
+    fn token(&self) -> Option<&'a str> {
+        let first = self.rest().chars().next()?;
+        if first.is_ascii_punctuation() {
+            return Some(&self.rest()[..first.len_utf8()]);
+        }
+        Some(&self.rest()[..self.run_length(|c| c.is_ascii_punctuation())])
+    }
+
+    /// An error for the next token, which was not the `expected` one
+    pub fn error(&self, expected: &str) -> ParseError {
+        ParseError {
+            position: self.position,
+            expected: expected.to_string(),
+            found: self.token().map_or(String::from("end of line"), |token| format!("\"{}\"", token)),
+        }
+    }
+
+    pub fn peek_char(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// The next token unless it is punctuation
+    pub fn peek_word(&self) -> Option<&'a str> {
+        self.token().filter(|token| !token.starts_with(|c: char| c.is_ascii_punctuation()))
+    }
+
+    /// Any single character that is not whitespace
+    pub fn char(&mut self) -> Result<char, ParseError> {
+        let c = self.peek_char().ok_or_else(|| self.error("a character"))?;
+        self.advance(c.len_utf8());
+        Ok(c)
+    }
+
+    pub fn expect_char(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.peek_char() != Some(expected) {
+            return Err(self.error(&format!("\"{}\"", expected)));
+        }
+        self.advance(expected.len_utf8());
+        Ok(())
+    }
+
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        let word = self.peek_word().ok_or_else(|| self.error("a word"))?;
+        self.advance(word.len());
+        Ok(word)
+    }
+
+    pub fn expect_word(&mut self, expected: &str) -> Result<(), ParseError> {
+        if self.peek_word() != Some(expected) {
+            return Err(self.error(&format!("\"{}\"", expected)));
+        }
+        self.advance(expected.len());
+        Ok(())
+    }
+
+    /// A non empty run of characters up to whitespace or one of the `delimiters`,
+    /// punctuation is part of it unless listed
+    pub fn word_until(&mut self, delimiters: &[char]) -> Result<&'a str, ParseError> {
+        let length = self.run_length(|c| delimiters.contains(&c));
+        if length == 0 {
+            return Err(self.error("a word"));
+        }
+        let word = &self.rest()[..length];
+        self.advance(length);
+        Ok(word)
+    }
+
+    /// A token made only of ASCII digits
+    pub fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let number = self
+            .peek_word()
+            .filter(|word| word.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|word| word.parse::<T>().ok().map(|number| (word.len(), number)));
+        let (length, number) = number.ok_or_else(|| self.error("a number"))?;
+        self.advance(length);
+        Ok(number)
+    }
+
+    /// One or more `item`s with `separator` between them, when an item fails the cursor
+    /// goes back to where the list started
+    pub fn separated_list<T, F>(&mut self, separator: char, mut item: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseError>,
+    {
+        let start = *self;
+        let mut read = || {
+            let mut items: Vec<T> = vec![item(self)?];
+            while self.peek_char() == Some(separator) {
+                self.advance(separator.len_utf8());
+                items.push(item(self)?);
+            }
+            Ok(items)
+        };
+        let items = read();
+        if items.is_err() {
+            *self = start;
+        }
+        items
+    }
+
+    pub fn expect_end(&mut self) -> Result<(), ParseError> {
+        if !self.is_at_end() {
+            return Err(self.error("end of line"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod parser_tests {
+    use crate::parser::{Cursor, ParseError};
+
+    #[test]
+    fn tokens_are_read_ignoring_whitespace() {
+        let mut cursor = Cursor::new("  12-3 \tab: c#d,e ");
+        assert_eq!(Ok(12), cursor.number::<u32>());
+        assert_eq!(Ok(()), cursor.expect_char('-'));
+        assert_eq!(Ok(3), cursor.number::<u32>());
+        assert_eq!(Some("ab"), cursor.peek_word());
+        assert_eq!(Ok('a'), cursor.char());
+        assert_eq!(Ok("b"), cursor.word());
+        assert_eq!(Ok(()), cursor.expect_char(':'));
+        assert_eq!(Ok("c#d"), cursor.word_until(&[',']));
+        assert_eq!(Ok(()), cursor.expect_char(','));
+        assert_eq!(Ok(()), cursor.expect_word("e"));
+        assert_eq!(Ok(()), cursor.expect_end());
+    }
+
+    #[test]
+    fn lists_are_read_up_to_the_last_separator() {
+        let mut cursor = Cursor::new("1, 2 ,3.");
+        assert_eq!(Ok(vec![1, 2, 3]), cursor.separated_list(',', |cursor| cursor.number::<u8>()));
+        assert_eq!(Some('.'), cursor.peek_char());
+
+        let mut cursor = Cursor::new("1, two");
+        assert_eq!(
+            Err(ParseError { position: 3, expected: "a number".to_string(), found: "\"two\"".to_string() }),
+            cursor.separated_list(',', |cursor| cursor.number::<u8>())
+        );
+        assert_eq!(0, cursor.position());
+    }
+
+    #[test]
+    fn failed_reads_report_the_next_token_and_consume_nothing() {
+        let mut cursor = Cursor::new("light red 2x bags");
+        let start = cursor.position();
+        cursor.word().unwrap();
+        cursor.word().unwrap();
+        assert_eq!("light red", cursor.since(start));
+        assert_eq!(
+            Err(ParseError { position: 10, expected: "a number".to_string(), found: "\"2x\"".to_string() }),
+            cursor.number::<u8>()
+        );
+        assert_eq!(
+            Err(ParseError { position: 10, expected: "\"bags\"".to_string(), found: "\"2x\"".to_string() }),
+            cursor.expect_word("bags")
+        );
+        assert_eq!(10, cursor.position());
+        assert_eq!(Ok("2x"), cursor.word());
+        assert_eq!(Ok("bags"), cursor.word());
+        assert_eq!(
+            "expected \".\" but found end of line at position 17",
+            cursor.expect_char('.').unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use crate::calendar::{Day, Year};
+use crate::day1::Day1;
+use crate::day2::Day2;
+use crate::day3::Day3;
+use crate::day4::Day4;
+use crate::day5::Day5;
+use crate::day6::Day6;
+use crate::day7::Day7;
+use crate::solution::Puzzle;
+
+pub struct Entry {
+    pub year: Year,
+    pub day: Day,
+    /// shared with the threads that answer each part
+    pub puzzle: Arc<dyn Puzzle>,
+}
+
+/// Every day that has a solution, in calendar order
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry { entries: Vec::new() }
+    }
+
+    pub fn register<P: Puzzle + 'static>(&mut self, year: Year, day: Day, puzzle: P) {
+        self.entries.retain(|entry| (entry.year, entry.day) != (year, day));
+        self.entries.push(Entry { year, day, puzzle: Arc::new(puzzle) });
+        self.entries.sort_by_key(|entry| (entry.year, entry.day));
+    }
+
+    pub fn get(&self, year: Year, day: Day) -> Option<&Entry> {
+        self.entries.iter().find(|entry| (entry.year, entry.day) == (year, day))
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The entries of a single year
+    pub fn entries_of(&self, year: Year) -> Vec<&Entry> {
+        self.entries.iter().filter(|entry| entry.year == year).collect()
+    }
+
+    pub fn years(&self) -> Vec<Year> {
+        let mut years: Vec<Year> = self.entries.iter().map(|entry| entry.year).collect();
+        years.dedup();
+        years
+    }
+
+    pub fn days(&self, year: Year) -> Vec<Day> {
+        self.entries_of(year).iter().map(|entry| entry.day).collect()
+    }
+}
+
+impl Default for Registry {
+    /// All the days solved in this crate
+    fn default() -> Self {
+        let mut registry = Registry::new();
+        registry.register(Year(2020), Day(1), Day1);
+        registry.register(Year(2020), Day(2), Day2);
+        registry.register(Year(2020), Day(3), Day3);
+        registry.register(Year(2020), Day(4), Day4);
+        registry.register(Year(2020), Day(5), Day5);
+        registry.register(Year(2020), Day(6), Day6);
+        registry.register(Year(2020), Day(7), Day7);
+        registry
+    }
+}
@@ -0,0 +1,424 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::bench::format_duration;
+use crate::calendar::{Day, Year};
+use crate::cancel::CancellationToken;
+use crate::input::InputProvider;
+use crate::registry::Entry;
+use crate::solution::{Part, Puzzle};
+
+/// How a single part ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Solved { answer: String },
+    /// the input could not be read or parsed, or the solver returned an error
+    Failed { error: String },
+    Panicked { message: String },
+    /// the part was still running when its time budget ran out
+    TimedOut { budget: Duration },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartReport {
+    pub part: Part,
+    pub status: Status,
+    /// zero when the part never ran
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayReport {
+    pub year: Year,
+    pub day: Day,
+    pub parse_time: Duration,
+    pub parts: Vec<PartReport>,
+}
+
+impl DayReport {
+    pub fn is_success(&self) -> bool {
+        self.parts.iter().all(|part| matches!(part.status, Status::Solved { .. }))
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+/// Runs `f`, turning both errors and panics into a `Status`
+fn guarded<T, F: FnOnce() -> Result<T, String>>(f: F) -> Result<T, Status> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(Status::Failed { error }),
+        Err(payload) => Err(Status::Panicked { message: panic_message(payload) }),
+    }
+}
+
+pub struct RunConfig {
+    pub workers: usize,
+    /// time budget of each part, `None` lets parts run for as long as they need
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        RunConfig {
+            workers: thread::available_parallelism().map_or(1, |workers| workers.get()),
+            timeout: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+/// Answers `part` on a thread of its own and waits for it at most `timeout`. A part
+/// still running by then is left behind: it stops early if it polls `token`, otherwise
+/// it runs on until the process exits
+fn answer_within(
+    puzzle: &Arc<dyn Puzzle>,
+    parsed: &Arc<dyn Any + Send + Sync>,
+    part: Part,
+    token: &CancellationToken,
+    timeout: Option<Duration>,
+) -> Result<String, Status> {
+    let (puzzle, parsed, part_token) = (Arc::clone(puzzle), Arc::clone(parsed), token.clone());
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(guarded(|| puzzle.answer_cancellable(parsed.as_ref(), part, &part_token)));
+    });
+    let received = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+    match received {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(Status::TimedOut { budget: timeout.unwrap_or_default() }),
+        Err(RecvTimeoutError::Disconnected) => Err(Status::Panicked { message: String::from("part stopped without an answer") }),
+    }
+}
+
+/// Parses the input once and solves every part, a failing part does not stop the others.
+/// Each part runs on its own thread with a token cancelled after `timeout`. The run stops
+/// waiting for a part once its budget is spent, and a part that outlives it is reported
+/// as timed out whatever it returned
+pub fn run_day(entry: &Entry, input: Result<String, String>, timeout: Option<Duration>) -> DayReport {
+    let start = Instant::now();
+    let parsed = input.map_err(|error| Status::Failed { error }).and_then(|input| {
+        guarded(|| entry.puzzle.parse(&input)).map(Arc::<dyn Any + Send + Sync>::from)
+    });
+    let parse_time = start.elapsed();
+
+    let parts = Part::ALL
+        .iter()
+        .map(|&part| match &parsed {
+            Ok(parsed) => {
+                let start = Instant::now();
+                let token = timeout.map_or_else(CancellationToken::new, CancellationToken::with_timeout);
+                let status = match answer_within(&entry.puzzle, parsed, part, &token, timeout) {
+                    Ok(_) | Err(Status::Failed { .. }) if token.is_cancelled() => {
+                        Status::TimedOut { budget: timeout.unwrap_or_default() }
+                    }
+                    Ok(answer) => Status::Solved { answer },
+                    Err(status) => status,
+                };
+                PartReport { part, status, elapsed: start.elapsed() }
+            }
+            Err(status) => PartReport { part, status: status.clone(), elapsed: Duration::from_secs(0) },
+        })
+        .collect();
+
+    DayReport { year: entry.year, day: entry.day, parse_time, parts }
+}
+
+/// A day waiting for a worker, with its input already read
+type Job<'a> = (&'a Entry, Result<String, String>);
+
+/// Runs the days of `entries` on a pool of `config.workers` threads, each worker picking
+/// the next day not started yet. Inputs are read up front since providers are not shared
+/// between threads. Reports come back in calendar order
+pub fn run_all(entries: &[&Entry], inputs: &dyn InputProvider, config: &RunConfig) -> Vec<DayReport> {
+    let jobs: Vec<Mutex<Option<Job>>> = entries
+        .iter()
+        .map(|&entry| Mutex::new(Some((entry, inputs.input(entry.year, entry.day).map_err(|error| error.to_string())))))
+        .collect();
+    let next = AtomicUsize::new(0);
+    let reports: Mutex<Vec<DayReport>> = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..config.workers.max(1).min(jobs.len()) {
+            scope.spawn(|| {
+                while let Some(job) = jobs.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    let (entry, input) = job.lock().unwrap().take().unwrap();
+                    let report = run_day(entry, input, config.timeout);
+                    reports.lock().unwrap().push(report);
+                }
+            });
+        }
+    });
+
+    let mut reports = reports.into_inner().unwrap();
+    reports.sort_by_key(|report| (report.year, report.day));
+    reports
+}
+
+pub fn render_table(reports: &[DayReport]) -> String {
+    let mut table = format!("{:<6} {:<6} {:<8} {:>12} {:>12}  {}\n", "year", "day", "part", "parse", "solve", "answer");
+    for report in reports {
+        for part in &report.parts {
+            let answer = match &part.status {
+                Status::Solved { answer } => answer.clone(),
+                Status::Failed { error } => format!("FAILED {}", error),
+                Status::Panicked { message } => format!("PANICKED {}", message),
+                Status::TimedOut { budget } => format!("TIMED OUT after {}", format_duration(*budget)),
+            };
+            table.push_str(&format!(
+                "{:<6} {:<6} {:<8} {:>12} {:>12}  {}\n",
+                report.year.to_string(),
+                report.day.to_string(),
+                part.part.to_string(),
+                format_duration(report.parse_time),
+                format_duration(part.elapsed),
+                answer
+            ));
+        }
+    }
+    let solved = reports.iter().filter(|report| report.is_success()).count();
+    table.push_str(&format!("{} of {} days solved\n", solved, reports.len()));
+    table
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// One object per day, durations in nanoseconds
+pub fn to_json(reports: &[DayReport]) -> String {
+    let days: Vec<String> = reports
+        .iter()
+        .map(|report| {
+            let parts: Vec<String> = report
+                .parts
+                .iter()
+                .map(|part| {
+                    let (status, detail) = match &part.status {
+                        Status::Solved { answer } => ("solved", format!("\"answer\": {}", json_string(answer))),
+                        Status::Failed { error } => ("failed", format!("\"error\": {}", json_string(error))),
+                        Status::Panicked { message } => ("panicked", format!("\"error\": {}", json_string(message))),
+                        Status::TimedOut { budget } => ("timed-out", format!("\"budget_ns\": {}", budget.as_nanos())),
+                    };
+                    format!(
+                        "{{\"part\": {}, \"status\": \"{}\", {}, \"solve_ns\": {}}}",
+                        part.part.number(),
+                        status,
+                        detail,
+                        part.elapsed.as_nanos()
+                    )
+                })
+                .collect();
+            format!(
+                "  {{\"year\": {}, \"day\": {}, \"parse_ns\": {}, \"parts\": [{}]}}",
+                report.year,
+                report.day,
+                report.parse_time.as_nanos(),
+                parts.join(", ")
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", days.join(",\n"))
+}
+
+#[cfg(test)]
+mod runner_tests {
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use crate::answers::{KnownAnswers, ANSWERS_PATH};
+    use crate::calendar::{Day, Year};
+    use crate::cancel::CancellationToken;
+    use crate::day1::Day1;
+    use crate::input::{InputError, InputProvider, ResourcesDir};
+    use crate::registry::{Entry, Registry};
+    use crate::runner::{json_string, run_all, run_day, to_json, RunConfig, Status};
+    use crate::solution::{Part, Solution};
+
+    struct Panics;
+
+    impl Solution for Panics {
+        type Parsed = u32;
+
+        fn parse(&self, input: &str) -> Result<u32, String> {
+            input.trim().parse::<u32>().map_err(|error| error.to_string())
+        }
+
+        fn part1(&self, parsed: &u32) -> Result<String, String> {
+            Ok((parsed * 2).to_string())
+        }
+
+        fn part2(&self, _parsed: &u32) -> Result<String, String> {
+            panic!("part 2 is not written yet")
+        }
+    }
+
+    /// Part 1 polls its token and spins until cancelled, part 2 ignores it and sleeps
+    struct Slow;
+
+    impl Solution for Slow {
+        type Parsed = ();
+
+        fn parse(&self, _input: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn part1(&self, _parsed: &()) -> Result<String, String> {
+            unreachable!()
+        }
+
+        fn part2(&self, _parsed: &()) -> Result<String, String> {
+            thread::sleep(Duration::from_millis(50));
+            Ok(String::from("late"))
+        }
+
+        fn part1_cancellable(&self, _parsed: &(), token: &CancellationToken) -> Result<String, String> {
+            while !token.is_cancelled() {
+                thread::yield_now();
+            }
+            Err(String::from("cancelled"))
+        }
+    }
+
+    /// Part 1 ignores its token and never returns
+    struct Hangs;
+
+    impl Solution for Hangs {
+        type Parsed = ();
+
+        fn parse(&self, _input: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn part1(&self, _parsed: &()) -> Result<String, String> {
+            loop {
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+
+        fn part2(&self, _parsed: &()) -> Result<String, String> {
+            Ok(String::from("done"))
+        }
+    }
+
+    fn config(workers: usize, timeout: Option<Duration>) -> RunConfig {
+        RunConfig { workers, timeout }
+    }
+
+    struct Fixed(&'static str);
+
+    impl InputProvider for Fixed {
+        fn input(&self, _year: Year, day: Day) -> Result<String, InputError> {
+            match day.0 {
+                3 => Err(InputError::MissingSession),
+                _ => Ok(self.0.to_string()),
+            }
+        }
+    }
+
+    #[test]
+    fn panics_and_errors_do_not_stop_other_days() {
+        let mut registry = Registry::new();
+        registry.register(Year(2020), Day(1), Panics);
+        registry.register(Year(2020), Day(2), Panics);
+        registry.register(Year(2020), Day(3), Panics);
+
+        let reports = run_all(&registry.entries_of(Year(2020)), &Fixed("21"), &config(2, None));
+        assert_eq!(vec![1, 2, 3], reports.iter().map(|report| report.day.0).collect::<Vec<u8>>());
+        assert_eq!(Status::Solved { answer: "42".to_string() }, reports[0].parts[0].status);
+        assert_eq!(Status::Panicked { message: "part 2 is not written yet".to_string() }, reports[1].parts[1].status);
+        assert!(matches!(&reports[2].parts[0].status, Status::Failed { error } if error.contains("AOC_SESSION")));
+        assert!(!reports[0].is_success());
+    }
+
+    #[test]
+    fn parse_errors_fail_every_part() {
+        let mut registry = Registry::new();
+        registry.register(Year(2020), Day(1), Panics);
+        let report = run_day(registry.get(Year(2020), Day(1)).unwrap(), Ok("x".to_string()), None);
+        for part in &report.parts {
+            assert_eq!(Status::Failed { error: "invalid digit found in string".to_string() }, part.status);
+        }
+        assert_eq!(Part::Two, report.parts[1].part);
+    }
+
+    #[test]
+    fn parts_over_budget_time_out() {
+        let budget = Duration::from_millis(20);
+        let mut registry = Registry::new();
+        registry.register(Year(2020), Day(1), Day1);
+        registry.register(Year(2020), Day(2), Slow);
+
+        let reports = run_all(&registry.entries_of(Year(2020)), &Fixed("1\n2\n3\n"), &config(2, Some(budget)));
+        assert_eq!(Status::TimedOut { budget }, reports[0].parts[1].status);
+        assert_eq!(Status::TimedOut { budget }, reports[1].parts[0].status);
+        assert_eq!(Status::TimedOut { budget }, reports[1].parts[1].status);
+    }
+
+    #[test]
+    fn parts_that_never_return_are_left_behind() {
+        let budget = Duration::from_millis(50);
+        let mut registry = Registry::new();
+        registry.register(Year(2020), Day(1), Hangs);
+
+        let start = Instant::now();
+        let reports = run_all(&registry.entries_of(Year(2020)), &Fixed(""), &config(1, Some(budget)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(Status::TimedOut { budget }, reports[0].parts[0].status);
+        assert_eq!(Status::Solved { answer: "done".to_string() }, reports[0].parts[1].status);
+    }
+
+    /// Parts without an answer in `answers.toml` may not be solved yet
+    #[test]
+    fn every_answered_part_is_solved() {
+        let registry = Registry::default();
+        let answers = KnownAnswers::load(ANSWERS_PATH).unwrap();
+        let entries: Vec<&Entry> = registry.entries().iter().collect();
+        let reports = run_all(&entries, &ResourcesDir::default(), &config(4, None));
+        assert_eq!(registry.entries().len(), reports.len());
+        for report in &reports {
+            for part in &report.parts {
+                if answers.get(report.year, report.day, part.part).is_some() {
+                    assert!(matches!(part.status, Status::Solved { .. }), "{:?}", report);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn json_escapes_strings() {
+        assert_eq!("\"say \\\"hi\\\"\\n\"", json_string("say \"hi\"\n"));
+        assert_eq!("\"\\u0007\"", json_string("\u{7}"));
+
+        let mut registry = Registry::new();
+        registry.register(Year(2020), Day(1), Panics);
+        let json = to_json(&run_all(&registry.entries_of(Year(2020)), &Fixed("21"), &config(1, None)));
+        assert!(json.starts_with("[\n  {\"year\": 2020, \"day\": 1, \"parse_ns\": "));
+        assert!(json.contains("{\"part\": 2, \"status\": \"panicked\", \"error\": \"part 2 is not written yet\", "));
+    }
+}
@@ -0,0 +1,332 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::calendar::{Day, Year};
+use crate::input::ResourcesDir;
+use crate::manifest::{checksum, Manifest};
+
+/// Days of this year predate the multi-year layout and live at the crate root as
+/// `src/dayN.rs`, every other year gets its own `src/yYEAR/` directory
+const ROOT_YEAR: Year = Year(2020);
+
+/// Solver module of a new day, `%YEAR%`, `%DAY%` and `%MODULE%` are replaced with the
+/// year, the day number and the module path
+const SOLVER_TEMPLATE: &str = r#"use crate::solution::Solution;
+
+pub struct Day%DAY%;
+
+impl Solution for Day%DAY% {
+    type Parsed = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>, String> {
+        Ok(input.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn part1(&self, _lines: &Vec<String>) -> Result<String, String> {
+        Err(String::from("part 1 is not solved yet"))
+    }
+
+    fn part2(&self, _lines: &Vec<String>) -> Result<String, String> {
+        Err(String::from("part 2 is not solved yet"))
+    }
+}
+
+#[cfg(test)]
+mod day%DAY%_tests {
+    use std::fs;
+    use crate::%MODULE%::Day%DAY%;
+    use crate::solution::{Part, Puzzle};
+
+    #[test]
+    #[ignore = "fill in the answer from the puzzle text"]
+    fn solve_example() {
+        let input = fs::read_to_string("resources/%YEAR%/examples/day-%DAY%/example.input").unwrap();
+        assert_eq!(Ok(String::from("")), Day%DAY%.solve(&input, Part::One));
+    }
+
+    #[test]
+    #[ignore = "fill in the accepted answer"]
+    fn solve_input() {
+        let input = fs::read_to_string("resources/%YEAR%/day-%DAY%-input").unwrap();
+        assert_eq!(Ok(String::from("")), Day%DAY%.solve(&input, Part::One));
+    }
+}
+"#;
+
+const EXAMPLE_ANSWERS_TEMPLATE: &str = "# answers given in the puzzle text for example.input\n# part1 = \n# part2 = \n";
+
+/// Manifest of the empty input a new day starts with, so that it validates until the
+/// real input is downloaded. `%SHA256%` is replaced with the checksum of an empty input
+const MANIFEST_TEMPLATE: &str = r#"# describes the empty input, update every key once the real input is in place
+account = "unknown"
+separator = "line"
+grammar = "{any}"
+count = 0
+sha256 = "%SHA256%"
+"#;
+
+/// Adds `line` to the block of lines starting with `prefix` followed by a number,
+/// keeping the block sorted by that number. Attribute lines right above a line of the
+/// block belong to it. Without such a block the line goes after the last line starting
+/// with `after`
+fn insert_sorted(source: &str, prefix: &str, key: u16, line: &str, after: &str) -> Result<String, String> {
+    let key_of = |candidate: &str| {
+        candidate
+            .trim_start()
+            .strip_prefix(prefix)
+            .map(|rest| rest.chars().take_while(|c| c.is_ascii_digit()).collect::<String>())
+            .and_then(|number| number.parse::<u16>().ok())
+    };
+    let mut lines: Vec<&str> = source.lines().collect();
+    let block: Vec<usize> = (0..lines.len()).filter(|&index| key_of(lines[index]).is_some()).collect();
+
+    if block.iter().any(|&index| key_of(lines[index]) == Some(key)) {
+        return Err(format!("{} is already there", line.trim()));
+    }
+    let position = match block.iter().find(|&&index| key_of(lines[index]).is_some_and(|other| other > key)) {
+        Some(&index) => {
+            let attributes = lines[..index].iter().rev().take_while(|line| line.trim_start().starts_with("#[")).count();
+            index - attributes
+        }
+        None => match block.last() {
+            Some(&last) => last + 1,
+            None => {
+                let last = (0..lines.len())
+                    .rfind(|&index| lines[index].trim_start().starts_with(after))
+                    .ok_or_else(|| format!("no line starting with {} to add {} next to", after, line.trim()))?;
+                last + 1
+            }
+        },
+    };
+    lines.insert(position, line);
+
+    let mut updated = lines.join("\n");
+    if source.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+/// Creates everything a new day needs under the crate at `root`: the solver module with
+/// its ignored tests, an empty input and its manifest, an example to fill in, the `mod`
+/// and registry lines, and an ignored answers test.
+/// Nothing is written when any of the files already exists or the day is already
+/// registered. Returns the paths created or changed
+pub fn new_day(root: &Path, year: Year, day: Day) -> Result<Vec<PathBuf>, String> {
+    let (solver_path, module) = if year == ROOT_YEAR {
+        (root.join(format!("src/day{}.rs", day)), format!("day{}", day))
+    } else {
+        (root.join(format!("src/y{}/day{}.rs", year, day)), format!("y{}::day{}", year, day))
+    };
+    let resources = ResourcesDir::new(root.join("resources"));
+    let examples = resources.root().join(year.to_string()).join("examples").join(format!("day-{}", day));
+    let solver = SOLVER_TEMPLATE
+        .replace("%YEAR%", &year.to_string())
+        .replace("%DAY%", &day.to_string())
+        .replace("%MODULE%", &module);
+
+    let mut files: Vec<(PathBuf, String)> = vec![
+        (solver_path, solver),
+        (resources.path(year, day), String::new()),
+        (Manifest::path_for(&resources.path(year, day)), MANIFEST_TEMPLATE.replace("%SHA256%", &checksum(""))),
+        (examples.join("example.input"), String::new()),
+        (examples.join("example.answers"), EXAMPLE_ANSWERS_TEMPLATE.to_string()),
+    ];
+    let existing: Vec<String> = files
+        .iter()
+        .filter(|(path, _)| path.exists())
+        .map(|(path, _)| path.display().to_string())
+        .collect();
+    if !existing.is_empty() {
+        return Err(format!("refusing to overwrite {}", existing.join(", ")));
+    }
+
+    let read = |path: &Path| fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error));
+    let lib_path = root.join("src/lib.rs");
+    let registry_path = root.join("src/registry.rs");
+    let answers_path = root.join("src/answers.rs");
+    let mut lib = read(&lib_path)?;
+    let mut registry = read(&registry_path)?;
+    let verify_prefix = format!("verify_{}_day", year);
+    let verify = format!(
+        "        #[ignore = \"add the answers to answers.toml\"]\n        {}{}: {}, {},",
+        verify_prefix, day, year, day
+    );
+    let answers = insert_sorted(&read(&answers_path)?, &verify_prefix, day.0.into(), &verify, "verify_")?;
+    let day_mod = format!("pub mod day{};", day);
+    let register_prefix = format!("registry.register(Year({}), Day(", year);
+
+    if year == ROOT_YEAR {
+        lib = insert_sorted(&lib, "pub mod day", day.0.into(), &day_mod, "pub mod day")?;
+        let use_line = format!("use crate::day{}::Day{};", day, day);
+        registry = insert_sorted(&registry, "use crate::day", day.0.into(), &use_line, "use crate::")?;
+        let register = format!("        {}{}), Day{});", register_prefix, day, day);
+        registry = insert_sorted(&registry, &register_prefix, day.0.into(), &register, "registry.register(")?;
+    } else {
+        let year_path = root.join(format!("src/y{}.rs", year));
+        if year_path.exists() {
+            let year_mod = insert_sorted(&read(&year_path)?, "pub mod day", day.0.into(), &day_mod, "pub mod day")?;
+            files.push((year_path, year_mod));
+        } else {
+            files.push((year_path, format!("{}\n", day_mod)));
+            let year_line = format!("pub mod y{};", year);
+            lib = insert_sorted(&lib, "pub mod y", year.0, &year_line, "pub mod day")?;
+        }
+        let register = format!("        {}{}), crate::{}::Day{});", register_prefix, day, module, day);
+        registry = insert_sorted(&registry, &register_prefix, day.0.into(), &register, "registry.register(")?;
+    }
+
+    let write = |path: &Path, contents: &str| {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| format!("{}: {}", parent.display(), error))?;
+        }
+        fs::write(path, contents).map_err(|error| format!("{}: {}", path.display(), error))
+    };
+    for (path, contents) in &files {
+        write(path, contents)?;
+    }
+    write(&lib_path, &lib)?;
+    write(&registry_path, &registry)?;
+    write(&answers_path, &answers)?;
+
+    let mut changed: Vec<PathBuf> = files.into_iter().map(|(path, _)| path).collect();
+    changed.push(lib_path);
+    changed.push(registry_path);
+    changed.push(answers_path);
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod scaffold_tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use crate::calendar::{Day, Year};
+    use crate::input::ResourcesDir;
+    use crate::manifest::validate;
+    use crate::registry::Registry;
+    use crate::scaffold::{insert_sorted, new_day};
+
+    /// A copy of the parts of this crate the scaffolding touches
+    fn crate_copy(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::copy("src/lib.rs", root.join("src/lib.rs")).unwrap();
+        fs::copy("src/registry.rs", root.join("src/registry.rs")).unwrap();
+        fs::copy("src/answers.rs", root.join("src/answers.rs")).unwrap();
+        root
+    }
+
+    /// The last day of 2020 this crate solves, the scaffolded days come after it
+    fn last_day() -> Day {
+        *Registry::default().days(Year(2020)).last().unwrap()
+    }
+
+    #[test]
+    fn lines_are_kept_sorted_by_day() {
+        let source = "use x;\npub mod day1;\npub mod day3;\n\npub mod other;\n";
+        assert_eq!(
+            "use x;\npub mod day1;\npub mod day2;\npub mod day3;\n\npub mod other;\n",
+            insert_sorted(source, "pub mod day", 2, "pub mod day2;", "pub mod day").unwrap()
+        );
+        assert_eq!(
+            "use x;\npub mod day1;\npub mod day3;\npub mod day12;\n\npub mod other;\n",
+            insert_sorted(source, "pub mod day", 12, "pub mod day12;", "pub mod day").unwrap()
+        );
+        assert_eq!(
+            "use x;\npub mod day1;\npub mod day3;\npub mod y2021;\n\npub mod other;\n",
+            insert_sorted(source, "pub mod y", 2021, "pub mod y2021;", "pub mod day").unwrap()
+        );
+        assert_eq!(
+            "#[a]\nday1;\n#[b]\nday2;\n#[c]\nday3;\n",
+            insert_sorted("#[a]\nday1;\n#[c]\nday3;\n", "day", 2, "#[b]\nday2;", "day").unwrap()
+        );
+        assert!(insert_sorted(source, "pub mod day", 3, "pub mod day3;", "pub mod day").is_err());
+        assert!(insert_sorted(source, "pub mod y", 2021, "pub mod y2021;", "mod nothing").is_err());
+    }
+
+    #[test]
+    fn new_day_is_created_and_registered() {
+        let root = crate_copy("new");
+        let (last, day) = (last_day(), Day(last_day().0 + 1));
+        let changed = new_day(&root, Year(2020), day).unwrap();
+        assert_eq!(8, changed.len());
+
+        let solver = fs::read_to_string(root.join(format!("src/day{}.rs", day))).unwrap();
+        assert!(solver.contains(&format!("impl Solution for Day{} {{", day)));
+        assert!(solver.contains(&format!("mod day{}_tests {{", day)));
+        assert!(solver.contains(&format!("\"resources/2020/day-{}-input\"", day)));
+        assert_eq!(2, solver.matches("#[ignore = ").count());
+        assert_eq!("", fs::read_to_string(root.join(format!("resources/2020/day-{}-input", day))).unwrap());
+        let report = validate(&ResourcesDir::new(root.join("resources")), Year(2020), day).unwrap();
+        assert!(report.is_valid(), "{}", report);
+        assert!(root.join(format!("resources/2020/examples/day-{}/example.answers", day)).exists());
+
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(lib.contains(&format!("pub mod day{};\npub mod day{};\n", last, day)));
+        let registry = fs::read_to_string(root.join("src/registry.rs")).unwrap();
+        assert!(registry.contains(&format!("use crate::day{0}::Day{0};\nuse crate::day{1}::Day{1};\n", last, day)));
+        assert!(registry.contains(&format!(
+            "Day({0}), Day{0});\n        registry.register(Year(2020), Day({1}), Day{1});\n",
+            last, day
+        )));
+        let answers = fs::read_to_string(root.join("src/answers.rs")).unwrap();
+        assert!(answers.contains(&format!(
+            "verify_2020_day{0}: 2020, {0},\n        #[ignore = \"add the answers to answers.toml\"]\n        verify_2020_day{1}: 2020, {1},\n",
+            last, day
+        )));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn new_year_gets_its_own_module() {
+        let root = crate_copy("year");
+        let year = Year(Registry::default().years().last().unwrap().0 + 1);
+        new_day(&root, year, Day(2)).unwrap();
+        new_day(&root, year, Day(1)).unwrap();
+
+        let solver = fs::read_to_string(root.join(format!("src/y{}/day1.rs", year))).unwrap();
+        assert!(solver.contains(&format!("use crate::y{}::day1::Day1;", year)));
+        assert!(solver.contains(&format!("\"resources/{}/examples/day-1/example.input\"", year)));
+        assert_eq!("pub mod day1;\npub mod day2;\n", fs::read_to_string(root.join(format!("src/y{}.rs", year))).unwrap());
+        assert!(root.join(format!("resources/{}/day-2-input", year)).exists());
+
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(lib.contains(&format!("\npub mod y{};\n", year)));
+        let registry = fs::read_to_string(root.join("src/registry.rs")).unwrap();
+        assert!(registry.contains(&format!(
+            concat!(
+                "        registry.register(Year({0}), Day(1), crate::y{0}::day1::Day1);\n",
+                "        registry.register(Year({0}), Day(2), crate::y{0}::day2::Day2);\n"
+            ),
+            year
+        )));
+        let answers = fs::read_to_string(root.join("src/answers.rs")).unwrap();
+        assert!(answers.contains(&format!(
+            concat!(
+                "        #[ignore = \"add the answers to answers.toml\"]\n        verify_{0}_day1: {0}, 1,\n",
+                "        #[ignore = \"add the answers to answers.toml\"]\n        verify_{0}_day2: {0}, 2,\n"
+            ),
+            year
+        )));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn existing_files_are_not_overwritten() {
+        let root = crate_copy("existing");
+        fs::create_dir_all(root.join("resources/2020")).unwrap();
+        fs::write(root.join("resources/2020/day-9-input"), "mine").unwrap();
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+
+        assert!(new_day(&root, Year(2020), Day(9)).unwrap_err().contains("day-9-input"));
+        assert_eq!("mine", fs::read_to_string(root.join("resources/2020/day-9-input")).unwrap());
+        assert!(!root.join("src/day9.rs").exists());
+        assert_eq!(lib, fs::read_to_string(root.join("src/lib.rs")).unwrap());
+
+        assert!(new_day(&root, Year(2020), Day(3)).unwrap_err().contains("already there"));
+        assert!(!root.join("src/day3.rs").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+use std::any::Any;
+use std::fmt;
+
+use crate::cancel::CancellationToken;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn from_number(number: u8) -> Option<Part> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "part {}", self.number())
+    }
+}
+
+/// The shape every day follows: the puzzle input is parsed once and both parts
+/// are answered from the parsed value. Answers are strings so days can return
+/// whatever number type suits them. The parsed value is shared with the threads
+/// that answer each part, so it has to be `Send` and `Sync`.
+pub trait Solution: Send + Sync {
+    type Parsed: Any + Send + Sync;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, String>;
+
+    fn part1(&self, parsed: &Self::Parsed) -> Result<String, String>;
+
+    fn part2(&self, parsed: &Self::Parsed) -> Result<String, String>;
+
+    /// Parts that can run for long override these to poll `token` and give up once
+    /// it is cancelled. By default the token is ignored
+    fn part1_cancellable(&self, parsed: &Self::Parsed, token: &CancellationToken) -> Result<String, String> {
+        let _ = token;
+        self.part1(parsed)
+    }
+
+    fn part2_cancellable(&self, parsed: &Self::Parsed, token: &CancellationToken) -> Result<String, String> {
+        let _ = token;
+        self.part2(parsed)
+    }
+}
+
+/// Object safe version of `Solution`, so days with different parsed types can
+/// live side by side in a `Registry`
+pub trait Puzzle: Send + Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any + Send + Sync>, String>;
+
+    /// `parsed` must come from `parse` of this same puzzle
+    fn answer_cancellable(&self, parsed: &dyn Any, part: Part, token: &CancellationToken) -> Result<String, String>;
+
+    fn answer(&self, parsed: &dyn Any, part: Part) -> Result<String, String> {
+        self.answer_cancellable(parsed, part, &CancellationToken::new())
+    }
+
+    fn solve(&self, input: &str, part: Part) -> Result<String, String> {
+        let parsed = self.parse(input)?;
+        self.answer(parsed.as_ref(), part)
+    }
+}
+
+impl<S: Solution> Puzzle for S {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any + Send + Sync>, String> {
+        Solution::parse(self, input).map(|parsed| Box::new(parsed) as Box<dyn Any + Send + Sync>)
+    }
+
+    fn answer_cancellable(&self, parsed: &dyn Any, part: Part, token: &CancellationToken) -> Result<String, String> {
+        let parsed = parsed
+            .downcast_ref::<S::Parsed>()
+            .ok_or_else(|| String::from("parsed input belongs to another puzzle"))?;
+        match part {
+            Part::One => self.part1_cancellable(parsed, token),
+            Part::Two => self.part2_cancellable(parsed, token),
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::calendar::{Day, Year};
+use crate::input::DEFAULT_BASE_URL;
+use crate::solution::Part;
+
+pub const HISTORY_PATH: &str = "guess-history.tsv";
+
+/// What the puzzle website said about an answer
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// wrong, without a hint about which way
+    Wrong,
+    /// an answer was sent too recently, nothing was checked
+    RateLimited { wait: Option<String> },
+    /// the part was already solved, nothing was checked
+    AlreadySolved,
+    /// the response did not look like any known one
+    Unknown,
+}
+
+impl Outcome {
+    /// Reads the outcome out of the HTML page returned after posting an answer
+    pub fn from_response(html: &str) -> Outcome {
+        if html.contains("That's the right answer") {
+            Outcome::Correct
+        } else if html.contains("You gave an answer too recently") {
+            let wait = html
+                .split("You have ")
+                .nth(1)
+                .and_then(|rest| rest.split(" left to wait").next())
+                .map(|wait| wait.to_string());
+            Outcome::RateLimited { wait }
+        } else if html.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if html.contains("your answer is too low") {
+            Outcome::TooLow
+        } else if html.contains("That's not the right answer") {
+            Outcome::Wrong
+        } else if html.contains("You don't seem to be solving the right level") {
+            Outcome::AlreadySolved
+        } else {
+            Outcome::Unknown
+        }
+    }
+
+    /// Whether the answer was actually judged
+    fn is_judged(&self) -> bool {
+        matches!(self, Outcome::Correct | Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong)
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::TooHigh => "too-high",
+            Outcome::TooLow => "too-low",
+            Outcome::Wrong => "wrong",
+            Outcome::RateLimited { .. } => "rate-limited",
+            Outcome::AlreadySolved => "already-solved",
+            Outcome::Unknown => "unknown",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Outcome> {
+        match key {
+            "correct" => Some(Outcome::Correct),
+            "too-high" => Some(Outcome::TooHigh),
+            "too-low" => Some(Outcome::TooLow),
+            "wrong" => Some(Outcome::Wrong),
+            "rate-limited" => Some(Outcome::RateLimited { wait: None }),
+            "already-solved" => Some(Outcome::AlreadySolved),
+            "unknown" => Some(Outcome::Unknown),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::RateLimited { wait: Some(wait) } => write!(f, "rate-limited, {} left to wait", wait),
+            _ => write!(f, "{}", self.key()),
+        }
+    }
+}
+
+/// Why an answer was not sent
+#[derive(Debug, PartialEq)]
+pub enum Refusal {
+    AlreadySolved { answer: String },
+    AlreadyGuessed { outcome: Outcome },
+    /// an answer this high, or lower, was already too high
+    TooHigh { bound: String },
+    /// an answer this low, or higher, was already too low
+    TooLow { bound: String },
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::AlreadySolved { answer } => write!(f, "already solved with {}", answer),
+            Refusal::AlreadyGuessed { outcome } => write!(f, "already guessed, it was {}", outcome),
+            Refusal::TooHigh { bound } => write!(f, "{} was already too high", bound),
+            Refusal::TooLow { bound } => write!(f, "{} was already too low", bound),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guess {
+    /// seconds since the unix epoch
+    pub timestamp: u64,
+    pub year: Year,
+    pub day: Day,
+    pub part: Part,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Every answer ever sent, stored as one tab separated line per guess
+pub struct History {
+    path: PathBuf,
+    guesses: Vec<Guess>,
+}
+
+impl History {
+    /// Starts empty when the file does not exist yet
+    pub fn load<P: Into<PathBuf>>(path: P) -> Result<History, String> {
+        let path = path.into();
+        let mut guesses: Vec<Guess> = Vec::new();
+        if path.exists() {
+            let data = fs::read_to_string(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+            for (index, line) in data.lines().enumerate().filter(|(_, line)| !line.is_empty()) {
+                let invalid = || format!("{}: line {} is not a guess", path.display(), index + 1);
+                let fields: Vec<&str> = line.split('\t').collect();
+                if fields.len() != 6 {
+                    return Err(invalid());
+                }
+                guesses.push(Guess {
+                    timestamp: fields[0].parse().map_err(|_| invalid())?,
+                    year: fields[1].parse().map_err(|_| invalid())?,
+                    day: fields[2].parse().map_err(|_| invalid())?,
+                    part: fields[3].parse().ok().and_then(Part::from_number).ok_or_else(invalid)?,
+                    answer: fields[4].to_string(),
+                    outcome: Outcome::from_key(fields[5]).ok_or_else(invalid)?,
+                });
+            }
+        }
+        Ok(History { path, guesses })
+    }
+
+    pub fn guesses(&self) -> &[Guess] {
+        &self.guesses
+    }
+
+    /// Refuses answers that the history already knows to be pointless
+    pub fn check(&self, year: Year, day: Day, part: Part, answer: &str) -> Result<(), Refusal> {
+        let guesses: Vec<&Guess> = self
+            .guesses
+            .iter()
+            .filter(|guess| (guess.year, guess.day, guess.part) == (year, day, part) && guess.outcome.is_judged())
+            .collect();
+
+        if let Some(correct) = guesses.iter().find(|guess| guess.outcome == Outcome::Correct) {
+            return Err(Refusal::AlreadySolved { answer: correct.answer.clone() });
+        }
+        if let Some(same) = guesses.iter().find(|guess| guess.answer == answer) {
+            return Err(Refusal::AlreadyGuessed { outcome: same.outcome.clone() });
+        }
+
+        if let Ok(number) = answer.parse::<i128>() {
+            let numeric = |outcome: Outcome| {
+                guesses
+                    .iter()
+                    .filter(move |guess| guess.outcome == outcome)
+                    .filter_map(|guess| guess.answer.parse::<i128>().ok())
+            };
+            if let Some(bound) = numeric(Outcome::TooHigh).filter(|&bound| number >= bound).min() {
+                return Err(Refusal::TooHigh { bound: bound.to_string() });
+            }
+            if let Some(bound) = numeric(Outcome::TooLow).filter(|&bound| number <= bound).max() {
+                return Err(Refusal::TooLow { bound: bound.to_string() });
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends the guess to the history file right away
+    pub fn record(&mut self, guess: Guess) -> Result<(), String> {
+        self.guesses.push(guess);
+        let data: String = self
+            .guesses
+            .iter()
+            .map(|guess| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\n",
+                    guess.timestamp,
+                    guess.year,
+                    guess.day,
+                    guess.part.number(),
+                    guess.answer,
+                    guess.outcome.key()
+                )
+            })
+            .collect();
+        fs::write(&self.path, data).map_err(|error| format!("{}: {}", self.path.display(), error))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SubmitError {
+    Refused(Refusal),
+    Http { url: String, status: u16 },
+    Network { url: String, error: String },
+    History(String),
+    MissingSession,
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Refused(refusal) => write!(f, "not sent, {}", refusal),
+            SubmitError::Http { url, status } => write!(f, "{}: HTTP status {}", url, status),
+            SubmitError::Network { url, error } => write!(f, "{}: {}", url, error),
+            SubmitError::History(error) => write!(f, "{}", error),
+            SubmitError::MissingSession => write!(f, "AOC_SESSION must hold the session cookie to submit answers"),
+        }
+    }
+}
+
+/// Posts answers to `<base_url>/<year>/day/<day>/answer` as the logged in account
+pub struct SubmitClient {
+    base_url: String,
+    session: String,
+}
+
+impl SubmitClient {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        SubmitClient { base_url: base_url.trim_end_matches('/').to_string(), session: session.to_string() }
+    }
+
+    /// Reads `AOC_SESSION`, and optionally `AOC_BASE_URL`
+    pub fn from_env() -> Result<Self, SubmitError> {
+        let session = env::var("AOC_SESSION").map_err(|_| SubmitError::MissingSession)?;
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        Ok(SubmitClient::new(&base_url, &session))
+    }
+
+    pub fn url(&self, year: Year, day: Day) -> String {
+        format!("{}/{}/day/{}/answer", self.base_url, year, day)
+    }
+
+    fn post(&self, year: Year, day: Day, part: Part, answer: &str) -> Result<Outcome, SubmitError> {
+        let url = self.url(year, day);
+        let level = part.number().to_string();
+        let response = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/SithEngineer/advent-of-code-2020")
+            .send_form(&[("level", level.as_str()), ("answer", answer)]);
+        match response {
+            Ok(response) => response
+                .into_string()
+                .map(|html| Outcome::from_response(&html))
+                .map_err(|error| SubmitError::Network { url, error: error.to_string() }),
+            Err(ureq::Error::Status(status, _)) => Err(SubmitError::Http { url, status }),
+            Err(error) => Err(SubmitError::Network { url, error: error.to_string() }),
+        }
+    }
+
+    /// Checks the answer against the history, sends it and records the outcome
+    pub fn submit(&self, history: &mut History, year: Year, day: Day, part: Part,
+                  answer: &str) -> Result<Outcome, SubmitError> {
+        history.check(year, day, part, answer).map_err(SubmitError::Refused)?;
+        let outcome = self.post(year, day, part, answer)?;
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        history
+            .record(Guess { timestamp, year, day, part, answer: answer.to_string(), outcome: outcome.clone() })
+            .map_err(SubmitError::History)?;
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod submit_tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use crate::calendar::{Day, Year};
+    use crate::solution::Part;
+    use crate::stub_server::StubServer;
+    use crate::submit::{History, Outcome, Refusal, SubmitClient, SubmitError};
+
+    fn history_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("aoc-history-{}-{}.tsv", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn responses_are_recognised() {
+        assert_eq!(Outcome::Correct, Outcome::from_response("<p>That's the right answer! You are one gold star closer.</p>"));
+        assert_eq!(Outcome::TooHigh, Outcome::from_response("That's not the right answer; your answer is too high."));
+        assert_eq!(Outcome::TooLow, Outcome::from_response("That's not the right answer; your answer is too low."));
+        assert_eq!(Outcome::Wrong, Outcome::from_response("That's not the right answer. If you're stuck"));
+        assert_eq!(
+            Outcome::RateLimited { wait: Some("39s".to_string()) },
+            Outcome::from_response("You gave an answer too recently. You have 39s left to wait.")
+        );
+        assert_eq!(Outcome::AlreadySolved, Outcome::from_response("You don't seem to be solving the right level."));
+        assert_eq!(Outcome::Unknown, Outcome::from_response("<html></html>"));
+    }
+
+    #[test]
+    fn answers_are_posted_and_recorded() {
+        let server = StubServer::start(vec![
+            (200, "That's not the right answer; your answer is too high."),
+            (200, "That's the right answer!"),
+        ]);
+        let path = history_path("posted");
+        let client = SubmitClient::new(&server.url, "secret");
+        let mut history = History::load(&path).unwrap();
+
+        assert_eq!(Ok(Outcome::TooHigh), client.submit(&mut history, Year(2020), Day(7), Part::Two, "500"));
+        assert_eq!(Ok(Outcome::Correct), client.submit(&mut history, Year(2020), Day(7), Part::Two, "126"));
+
+        let requests = server.requests();
+        assert!(requests[0].starts_with("POST /2020/day/7/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("level=2&answer=500"));
+
+        let reloaded = History::load(&path).unwrap();
+        assert_eq!(history.guesses(), reloaded.guesses());
+        assert_eq!(2, reloaded.guesses().len());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn pointless_answers_are_refused_before_sending() {
+        let server = StubServer::start(vec![
+            (200, "That's not the right answer; your answer is too high."),
+            (200, "That's not the right answer; your answer is too low."),
+            (200, "You gave an answer too recently. You have 10s left to wait."),
+        ]);
+        let path = history_path("refused");
+        let client = SubmitClient::new(&server.url, "secret");
+        let mut history = History::load(&path).unwrap();
+
+        client.submit(&mut history, Year(2020), Day(1), Part::One, "1000").unwrap();
+        client.submit(&mut history, Year(2020), Day(1), Part::One, "10").unwrap();
+        client.submit(&mut history, Year(2020), Day(1), Part::One, "500").unwrap();
+
+        let refused = |answer: &str| match client.submit(&mut History::load(&path).unwrap(), Year(2020), Day(1), Part::One, answer) {
+            Err(SubmitError::Refused(refusal)) => Some(refusal),
+            _ => None,
+        };
+        assert_eq!(Some(Refusal::AlreadyGuessed { outcome: Outcome::TooHigh }), refused("1000"));
+        assert_eq!(Some(Refusal::TooHigh { bound: "1000".to_string() }), refused("2000"));
+        assert_eq!(Some(Refusal::TooLow { bound: "10".to_string() }), refused("3"));
+        assert_eq!(3, server.requests().len());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn solved_part_is_refused() {
+        let server = StubServer::start(vec![(200, "That's the right answer!")]);
+        let path = history_path("solved");
+        let client = SubmitClient::new(&server.url, "secret");
+        let mut history = History::load(&path).unwrap();
+
+        client.submit(&mut history, Year(2020), Day(2), Part::One, "42").unwrap();
+        assert_eq!(
+            Err(SubmitError::Refused(Refusal::AlreadySolved { answer: "42".to_string() })),
+            client.submit(&mut history, Year(2020), Day(2), Part::One, "43")
+        );
+        assert!(history.check(Year(2020), Day(2), Part::Two, "43").is_ok());
+        fs::remove_file(path).unwrap();
+    }
+}